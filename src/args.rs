@@ -1,25 +1,72 @@
+use crate::{
+    config,
+    error::{Error, Result},
+    keys::Layout,
+};
 use std::{str::FromStr, time::Duration};
 
-const HELP_MSG: &str = "\
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const HELP_MSG: &str = "\
+USAGE:
+    boulder_dash [FLAGS] [OPTIONS] [LEVEL]...
+    Options take `--opt value` or `--opt=value`, short flags can be
+    grouped like `-pl level`. Everything after `--` is a level path.
 FLAGS:
     -h, --help
         Show this message.
+    --version
+        Print the version.
     -p, --pause
         Launch paused.
+    --scores
+        Print the high score table and exit: the best score,
+        the fewest ticks and the fastest time to win each level.
+        Levels have no time limit, the fastest time stands in for
+        the most time left.
+    --check
+        Validate the levels and exit.
+    --trim
+        Editor: trim trailing void when moving left or up.
 OPTIONS:
     -l, --level <string>
-        Required.
+        Required, can also be given without `-l`.
         Specify a level to run.
         Can be used multiple times.
+        BDCFF files load all of their caves, `file#n` picks one.
+    -k, --layout <string>
+        * qwerty
+        * colemak (default)
+        Select the key preset.
+        `~/.config/boulder_dash/keys` can rebind keys on top of it.
+    --config <string>
+        Read the defaults from this file instead of
+        `~/.config/boulder_dash/config`. It has `key = value` lines
+        for mode, size, delay, pause, trim, layout, theme, levels
+        (a directory to look up levels in), new_size and autosave.
+    --theme <string>
+        Directory with the GUI sprites. (default: assets/sprites/)
     -m, --mode <string>
         * gui
         * tui (default)
         * cli
         Select the interaction mode.
+    --pack <string>
+        Load a level pack: a manifest file or a directory with one.
+        Its levels go before the ones from `-l`.
+    --resume <string>
+        Resume the game from a save file.
+        The progress is saved back to it on quit.
     -r, --run <string>
         * g / b / game (default)
         * e / editor
         Select the program mode.
+    --new-size <WxH>
+        Editor: size of the levels created for paths that don't exist,
+        with the border walls. (default: 20x10)
+    --autosave <integer>
+        Editor: write unsaved changes to `<level>.recovery` every
+        this many seconds, the next start offers to restore them.
     -s, --size <integer>
         Object size for GUI. (default 30 pixels).
     -d, --delay <integer>
@@ -62,14 +109,54 @@ impl FromStr for ProgramMode {
     }
 }
 
+// Options that take a value, everything else is a flag
+const VALUE_OPTIONS: [&str; 18] = [
+    "-l",
+    "--level",
+    "-k",
+    "--layout",
+    "-m",
+    "--mode",
+    "-r",
+    "--run",
+    "-s",
+    "--size",
+    "-d",
+    "--delay",
+    "--config",
+    "--theme",
+    "--pack",
+    "--resume",
+    "--new-size",
+    "--autosave",
+];
+
+// What the command line asks for, `main` prints the help and the version
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(Arguments),
+    Help,
+    Version,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Arguments {
     pub size: u32,
     pub pause: bool,
+    pub scores: bool,
+    pub check: bool,
+    pub trim: bool,
     pub delay: Duration,
     pub level_paths: Vec<String>,
+    pub pack_path: Option<String>,
+    pub resume_path: Option<String>,
     pub program_mode: ProgramMode,
     pub interaction_mode: InteractionMode,
+    pub layout: Layout,
+    pub theme: Option<String>,
+    pub level_dirs: Vec<String>,
+    pub new_size: (usize, usize),
+    pub autosave: Option<Duration>,
 }
 
 impl Default for Arguments {
@@ -77,54 +164,160 @@ impl Default for Arguments {
         Self {
             size: 30,
             pause: false,
+            scores: false,
+            check: false,
+            trim: false,
             delay: Duration::from_millis(1000),
             level_paths: vec![],
+            pack_path: None,
+            resume_path: None,
             program_mode: ProgramMode::Game,
             interaction_mode: InteractionMode::Tui,
+            layout: Layout::default(),
+            theme: None,
+            level_dirs: vec![],
+            new_size: (20, 10),
+            autosave: None,
         }
     }
 }
 
-fn parse_arg<T, E>(arg_opt: Option<String>, arg_name: &str) -> Result<T, String>
+fn parse_arg<T, E>(arg_opt: Option<String>, arg_name: &str) -> Result<T>
 where
     T: FromStr<Err = E>,
     E: ToString,
 {
     match arg_opt {
-        Some(arg) => arg.parse().map_err(|e: E| e.to_string()),
-        None => Err(format!("Missing value for `{arg_name}`!")),
+        Some(arg) => arg.parse().map_err(|e: E| Error::Args(e.to_string())),
+        None => Err(Error::Args(format!("Missing value for `{arg_name}`!"))),
+    }
+}
+
+// `WxH` with both sides positive
+pub(crate) fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let error = || format!("Can't parse `{value}` as a valid size, expected `WxH`!");
+    let (width, height) = value.split_once('x').ok_or_else(error)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(error()),
     }
 }
 
+// Split the arguments into `(option, value)` pairs.
+// `--opt=value`, `-abc` as `-a -b -c`, `-d200` as `-d 200`, a level path as `-l path`.
+fn split_options(args: impl Iterator<Item = String>) -> Result<Vec<(String, Option<String>)>> {
+    let mut args = args.peekable();
+    let mut options = vec![];
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            options.extend(args.by_ref().map(|path| ("-l".to_string(), Some(path))));
+            break;
+        }
+        if arg == "-" || !arg.starts_with('-') {
+            options.push(("-l".to_string(), Some(arg)));
+            continue;
+        }
+
+        let mut group = vec![];
+        if let Some(long) = arg.strip_prefix("--") {
+            match long.split_once('=') {
+                Some((name, value)) => group.push((format!("--{name}"), Some(value.to_string()))),
+                None => group.push((arg.clone(), None)),
+            }
+        } else {
+            let chars: Vec<char> = arg.chars().skip(1).collect();
+            for (idx, chr) in chars.iter().enumerate() {
+                let name = format!("-{chr}");
+                if VALUE_OPTIONS.contains(&name.as_str()) && idx + 1 < chars.len() {
+                    group.push((name, Some(chars[idx + 1..].iter().collect())));
+                    break;
+                }
+                group.push((name, None));
+            }
+        }
+
+        for (name, value) in group {
+            let takes_value = VALUE_OPTIONS.contains(&name.as_str());
+            let value = match value {
+                Some(_) if !takes_value => {
+                    return Err(Error::Args(format!("`{name}` doesn't take a value!")))
+                }
+                None if takes_value => match args.next() {
+                    Some(value) => Some(value),
+                    None => return Err(Error::Args(format!("Missing value for `{name}`!"))),
+                },
+                value => value,
+            };
+            options.push((name, value));
+        }
+    }
+
+    Ok(options)
+}
+
 impl Arguments {
-    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+    // The config file provides the defaults, flags override them
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<Command> {
+        let options = split_options(args)?;
+        let has = |names: &[&str]| {
+            options
+                .iter()
+                .any(|(name, _)| names.contains(&name.as_str()))
+        };
+        if has(&["-h", "--help"]) {
+            return Ok(Command::Help);
+        }
+        if has(&["--version"]) {
+            return Ok(Command::Version);
+        }
+
+        let config_path = options
+            .iter()
+            .rev()
+            .find_map(|(name, value)| (name == "--config").then_some(value.as_deref()))
+            .flatten();
         let mut config = Self::default();
+        config::load(&mut config, config_path)?;
 
-        while let Some(arg) = args.next() {
-            match arg.as_str() {
-                "-h" | "--help" => {
-                    println!("{HELP_MSG}");
-                    std::process::exit(0);
-                }
+        for (name, value) in options {
+            let name = name.as_str();
+            match name {
                 "-p" | "--pause" => config.pause = true,
+                "--scores" => config.scores = true,
+                "--check" => config.check = true,
+                "--trim" => config.trim = true,
 
-                "-s" | "--size" => config.size = parse_arg(args.next(), arg.as_str())?,
-                "-d" | "--delay" => {
-                    config.delay = Duration::from_millis(parse_arg(args.next(), arg.as_str())?);
+                "-s" | "--size" => config.size = parse_arg(value, name)?,
+                "-d" | "--delay" => config.delay = Duration::from_millis(parse_arg(value, name)?),
+                "-l" | "--level" => config.level_paths.push(parse_arg(value, name)?),
+                "--pack" => config.pack_path = Some(parse_arg(value, name)?),
+                "--resume" => config.resume_path = Some(parse_arg(value, name)?),
+                "-r" | "--run" => config.program_mode = parse_arg(value, name)?,
+                "-m" | "--mode" => config.interaction_mode = parse_arg(value, name)?,
+                "-k" | "--layout" => config.layout = parse_arg(value, name)?,
+                "--theme" => config.theme = Some(parse_arg(value, name)?),
+                "--autosave" => {
+                    config.autosave = Some(Duration::from_secs(parse_arg(value, name)?));
+                }
+                "--new-size" => {
+                    let value: String = parse_arg(value, name)?;
+                    config.new_size = parse_size(&value).map_err(Error::Args)?;
                 }
-                "-l" | "--level" => config
-                    .level_paths
-                    .push(parse_arg(args.next(), arg.as_str())?),
-                "-r" | "--run" => config.program_mode = parse_arg(args.next(), arg.as_str())?,
-                "-m" | "--mode" => config.interaction_mode = parse_arg(args.next(), arg.as_str())?,
+                "--config" => (), // Already loaded
 
-                _ => return Err(format!("Unrecognized option `{arg}`!")),
+                _ => return Err(Error::Args(format!("Unrecognized option `{name}`!"))),
             }
         }
 
-        match config.level_paths.first() {
-            Some(_) => Ok(config),
-            None => Err("Specify a level path with `-l some/path`!".into()),
+        let can_resume = config.resume_path.is_some() && config.program_mode == ProgramMode::Game;
+        let has_levels = !config.level_paths.is_empty() || config.pack_path.is_some();
+        if !has_levels && !can_resume && !config.scores {
+            return Err(Error::Args(
+                "Specify a level path with `-l some/path` or just `some/path`!".into(),
+            ));
         }
+
+        Ok(Command::Run(config))
     }
 }
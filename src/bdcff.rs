@@ -0,0 +1,110 @@
+use crate::error::{Error, Result};
+use std::fs;
+
+// Boulder Dash Common File Format, only caves with a [map] section are supported.
+// A cave is addressed as `file#n`, n starting from 1.
+
+pub fn is_bdcff(contents: &str) -> bool {
+    contents
+        .trim_start()
+        .get(..7)
+        .is_some_and(|header| header.eq_ignore_ascii_case("[bdcff]"))
+}
+
+// Map element codes onto our chars, the rest is approximated with void
+const fn convert(code: char) -> char {
+    match code {
+        'W' | 'w' | 'm' | 'x' | 'v' => '#', // steel, brick, magic and expanding walls
+        '.' => '*',                         // dirt
+        'r' => 'O',                         // boulder
+        'd' => '+',                         // diamond
+        'P' => 'p',                         // inbox
+        _ => ' ',                           // space, outboxes, creatures, amoeba, ...
+    }
+}
+
+// Every cave as a level in our own char format
+pub fn parse(contents: &str) -> Result<Vec<String>, &'static str> {
+    let mut caves = vec![];
+    let mut in_cave = false;
+    let mut map: Option<Vec<String>> = None;
+
+    for line in contents.lines().map(|l| l.trim_end_matches('\r')) {
+        let section = line.trim().to_lowercase();
+
+        if let Some(rows) = &mut map {
+            if section == "[/map]" {
+                if !in_cave {
+                    return Err("[map] outside of a [cave]!");
+                }
+                caves.push(rows.join("\n"));
+                map = None;
+            } else {
+                rows.push(line.chars().map(convert).collect());
+            }
+            continue;
+        }
+
+        match section.as_str() {
+            "[cave]" => in_cave = true,
+            "[/cave]" => in_cave = false,
+            "[map]" => map = Some(vec![]),
+            _ => (), // cave properties aren't supported
+        }
+    }
+
+    if map.is_some() {
+        return Err("Unterminated [map] section!");
+    }
+    if caves.is_empty() {
+        return Err("No caves with a [map] section found!");
+    }
+
+    Ok(caves)
+}
+
+fn split_cave(path: &str) -> Option<(&str, usize)> {
+    let (file, idx) = path.rsplit_once('#')?;
+    Some((file, idx.parse().ok().filter(|&idx| idx > 0)?))
+}
+
+// Read a level in our format, converting BDCFF caves on the fly
+pub fn read_level(path: &str) -> Result<String> {
+    let (file, idx) = match split_cave(path) {
+        Some((file, idx)) if fs::metadata(path).is_err() => (file, idx),
+        _ => (path, 1),
+    };
+
+    let contents = fs::read_to_string(file).map_err(|e| Error::io(file, e))?;
+    if !is_bdcff(&contents) {
+        return Ok(contents);
+    }
+
+    let mut caves = parse(&contents).map_err(|e| Error::format(file, e))?;
+    if idx > caves.len() {
+        let message = format!("There are only {} caves!", caves.len());
+        return Err(Error::format(file, message));
+    }
+    Ok(caves.swap_remove(idx - 1))
+}
+
+// A BDCFF file becomes one path per cave, other paths are kept as is
+pub fn expand_path(path: &str) -> Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) if is_bdcff(&contents) => {
+            let caves = parse(&contents).map_err(|e| Error::format(path, e))?;
+            Ok((1..=caves.len())
+                .map(|idx| format!("{path}#{idx}"))
+                .collect())
+        }
+        _ => Ok(vec![path.to_string()]),
+    }
+}
+
+// BDCFF files are never overwritten, the edited cave goes next to them
+pub fn save_path(path: &str) -> String {
+    match split_cave(path) {
+        Some((file, idx)) if fs::metadata(path).is_err() => format!("{file}.cave{idx}"),
+        _ => path.to_string(),
+    }
+}
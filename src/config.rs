@@ -0,0 +1,69 @@
+use crate::{
+    args::{self, Arguments},
+    dirs,
+    error::{Error, Result},
+};
+use std::{fs, io, str::FromStr, time::Duration};
+
+const CONFIG_NAME: &str = "config";
+
+// One `key = value` pair per line, `#` starts a comment:
+//   mode <gui|tui|cli>, size <integer>, delay <ms>, pause <true|false>, trim <true|false>,
+//   layout <qwerty|colemak>, theme <sprite directory>, levels <directory>, new_size <WxH>,
+//   autosave <seconds>
+// `levels` can repeat, relative level paths are looked up in these directories too.
+// Without `--config` the file is optional and read from the config directory.
+pub fn load(args: &mut Arguments, path: Option<&str>) -> Result<()> {
+    let (path, required) = match path {
+        Some(path) => (path.to_string(), true),
+        None => {
+            let path = dirs::config_dir().join(CONFIG_NAME);
+            (path.to_string_lossy().into_owned(), false)
+        }
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if !required && e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Error::io(path, e)),
+    };
+
+    for (idx, line) in contents.lines().map(str::trim).enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let result = match line.split_once('=') {
+            Some((key, value)) => apply(args, key.trim(), value.trim()),
+            None => Err("expected `key = value`".to_string()),
+        };
+        result.map_err(|e| Error::format(&path, format!("line {}: {e}!", idx + 1)))?;
+    }
+
+    Ok(())
+}
+
+fn apply(args: &mut Arguments, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "mode" => args.interaction_mode = parse(value)?,
+        "size" => args.size = parse(value)?,
+        "delay" => args.delay = Duration::from_millis(parse(value)?),
+        "pause" => args.pause = parse(value)?,
+        "trim" => args.trim = parse(value)?,
+        "layout" => args.layout = parse(value)?,
+        "theme" => args.theme = Some(value.to_string()),
+        "levels" => args.level_dirs.push(value.to_string()),
+        "new_size" => args.new_size = args::parse_size(value)?,
+        "autosave" => args.autosave = Some(Duration::from_secs(parse(value)?)),
+        _ => return Err(format!("unrecognized key `{key}`")),
+    }
+    Ok(())
+}
+
+fn parse<T>(value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: ToString,
+{
+    value.parse().map_err(|e: T::Err| e.to_string())
+}
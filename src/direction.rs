@@ -1,6 +1,4 @@
-use crate::interaction::Input;
-
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -8,21 +6,6 @@ pub enum Direction {
     Right,
 }
 
-impl TryFrom<Input> for Direction {
-    type Error = ();
-
-    fn try_from(input: Input) -> Result<Self, Self::Error> {
-        match input {
-            Input::Up | Input::W => Ok(Self::Up),
-            Input::Down | Input::S => Ok(Self::Down),
-            Input::Left | Input::A => Ok(Self::Left),
-            Input::Right | Input::D => Ok(Self::Right),
-
-            _ => Err(()),
-        }
-    }
-}
-
 impl Direction {
     pub const fn apply_to(&self, point: &(usize, usize)) -> (usize, usize) {
         let (x, y) = match self {
@@ -0,0 +1,26 @@
+use std::{env, path::PathBuf};
+
+// `$XDG_VAR/boulder_dash` or `$HOME/<fallback>/boulder_dash`
+fn xdg_dir(xdg_var: &str, fallback: &str) -> PathBuf {
+    let base = env::var_os(xdg_var)
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(
+            || {
+                env::var_os("HOME")
+                    .map(PathBuf::from)
+                    .unwrap_or_default()
+                    .join(fallback)
+            },
+            PathBuf::from,
+        );
+
+    base.join("boulder_dash")
+}
+
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
@@ -1,35 +1,175 @@
 use crate::{
-    args::Arguments,
+    args::{Arguments, ProgramMode},
+    bdcff,
     direction::Direction,
-    interaction::{Drawable, Input, Interaction, Mode},
-    objects::{Labels, Object},
+    error::{Error, Result},
+    game::Game,
+    interaction::{Action, Drawable, Interaction},
+    keys::Keymap,
+    objects::{Labels, Object, Wall},
+    pack::Pack,
+    validate::{self, Issue},
     Point,
 };
-use std::{collections::HashSet, error::Error, fs, io, thread, time::Duration};
+use std::{
+    collections::{HashSet, VecDeque},
+    fs::{self, File},
+    io::{self, Write},
+    mem, thread,
+    time::{Duration, Instant},
+};
+
+const HISTORY_LEN: usize = 100;
+
+// Asked in the status line, answered with typed chars
+enum Prompt {
+    SaveAs(String), // the path typed so far
+    Restore,        // an autosave was left over
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    #[default]
+    Pen,
+    Fill,
+    Line,
+    Rect,
+    FilledRect,
+}
+
+impl Tool {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Pen => "pen",
+            Self::Fill => "fill",
+            Self::Line => "line",
+            Self::Rect => "rectangle",
+            Self::FilledRect => "filled rectangle",
+        }
+    }
+
+    const fn next(self) -> Self {
+        match self {
+            Self::Pen => Self::Fill,
+            Self::Fill => Self::Line,
+            Self::Line => Self::Rect,
+            Self::Rect => Self::FilledRect,
+            Self::FilledRect => Self::Pen,
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct Editor {
-    file_name: String,
+    level_idx: usize,
+    level_paths: Vec<String>,
+    pack_name: Option<String>,
+    keymap: Keymap,      // for the key hints in the status
+    game_keymap: Keymap, // swapped in for playtests
     cursor: Point,
     pen_down: bool,
+    stroke_saved: bool, // the undo step of the current stroke is taken
+    tool: Tool,
+    tool_start: Option<Point>, // the first point of a line or a rectangle
+    last_drag: Option<Point>,  // where the mouse painted last
     current_object: usize,
     damaged: HashSet<Point>,
     matrix: Vec<Vec<Object>>,
+    issues: Vec<Issue>,          // as of the last load or save
+    undo: Vec<Vec<Vec<Object>>>, // matrices before each edit, the last one is the newest
+    redo: Vec<Vec<Vec<Object>>>,
+    anchor: Option<Point>, // the selection goes from here to the cursor
+    clipboard: Vec<Vec<Object>>,
+    pasting: bool,          // the clipboard is previewed at the cursor
+    view: Vec<Vec<Object>>, // the matrix with the preview, what gets drawn
+    highlight: Option<(Point, Point)>,
+    delay: Duration, // for playtests
+    pause: bool,
+    trim: bool,                 // drop trailing void when moving left or up
+    new_size: (usize, usize),   // for level paths that don't exist yet
+    autosave: Option<Duration>, // how often unsaved changes go to the recovery file
+    saved: String,              // the contents as of the last load or save
+    confirm: Option<Action>,    // pressed once with unsaved changes, again to go ahead
+    prompt: Option<Prompt>,
+    warning: Option<String>, // a failed save or autosave, until the next save
+    header: String,          // metadata lines before the grid, written back as is
+    endings: Vec<&'static str>, // of each loaded row, the last one is "" without a final newline
+}
+
+// Cells on the straight line between two points, both included
+fn line((x1, y1): Point, (x2, y2): Point) -> Vec<Point> {
+    let steps = x1.abs_diff(x2).max(y1.abs_diff(y2));
+    // Move from `a` towards `b` by `step / steps` of the way, rounded
+    let lerp = |a: usize, b: usize, step: usize| match a.abs_diff(b) * step * 2 + steps {
+        _ if steps == 0 => a,
+        offset if b >= a => a + offset / (2 * steps),
+        offset => a - offset / (2 * steps),
+    };
+
+    (0..=steps)
+        .map(|step| (lerp(x1, x2, step), lerp(y1, y2, step)))
+        .collect()
+}
+
+// Walls around void, `width` by `height` with the walls
+fn new_level((width, height): (usize, usize)) -> String {
+    let rows: Vec<String> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let edge = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+                    if edge {
+                        Wall.char()
+                    } else {
+                        Object::default().char()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    rows.join("\n")
+}
+
+// Write a temporary file next to `path` and rename it over `path`,
+// so a crash leaves either the old or the new contents
+fn write_atomic(path: &str, contents: &str) -> Result<()> {
+    let tmp = format!("{path}.tmp");
+    // Synced before the rename, or a power loss can bring it back empty
+    let written = File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp); // a partial file is of no use
+        return Err(Error::io(tmp, e));
+    }
+    fs::rename(&tmp, path).map_err(|e| Error::io(path, e))
+}
+
+// Top left and bottom right corners of the rectangle spanned by two points
+fn span((x1, y1): Point, (x2, y2): Point) -> (Point, Point) {
+    ((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2)))
 }
 
 impl Drawable for Editor {
     fn get_cursor(&self) -> Option<&Point> {
         Some(&self.cursor)
     }
+    fn get_highlight(&self) -> Option<(Point, Point)> {
+        self.highlight
+    }
+    fn get_palette(&self) -> Option<(Vec<Object>, usize)> {
+        Some((Object::get_all_displayable(), self.current_object))
+    }
 
     fn get_damaged(&mut self) -> Vec<Point> {
-        std::mem::take(&mut self.damaged).into_iter().collect()
+        mem::take(&mut self.damaged).into_iter().collect()
     }
     fn get_objects(&self) -> &Vec<Vec<Object>> {
-        &self.matrix
+        &self.view
     }
     fn get_object(&self, (x, y): Point) -> Option<&Object> {
-        self.matrix.get(y)?.get(x)
+        self.view.get(y)?.get(x)
     }
 
     fn get_status(&self) -> String {
@@ -40,110 +180,746 @@ impl Drawable for Editor {
             .collect();
         objects[self.current_object].insert(0, '[');
         objects[self.current_object].push(']');
-        let pen = if self.pen_down { "down" } else { "up" };
+        let tool = match (self.tool, self.tool_start) {
+            (Tool::Pen, _) => format!("Pen {}", if self.pen_down { "down" } else { "up" }),
+            (tool, Some((x, y))) => format!("Tool: {} from ({x}, {y})", tool.name()),
+            (tool, None) => format!("Tool: {}", tool.name()),
+        };
+
+        let level = if self.level_paths.len() > 1 {
+            format!(
+                "{}level {}/{}: ",
+                self.pack_name
+                    .as_ref()
+                    .map_or(String::new(), |name| format!("{name}: ")),
+                self.level_idx + 1,
+                self.level_paths.len(),
+            )
+        } else {
+            String::new()
+        };
+        let modified = if self.is_dirty() { " (modified)" } else { "" };
 
-        format!("Pen {pen}\nCursor pos: ({x}, {y})\n{}", objects.join(" "))
+        let mut status = format!(
+            "{tool}\n{level}{}{modified}\nCursor pos: ({x}, {y})\n{}",
+            self.get_level_path(),
+            objects.join(" ")
+        );
+        if self.level_paths.len() > 1 {
+            status += &format!(
+                "\n{}",
+                self.keymap.hints(&[(Action::NextLevel, "next level")])
+            );
+        }
+        if let Some(((x1, y1), (x2, y2))) = self.highlight {
+            let size = format!("{}x{}", x2 - x1 + 1, y2 - y1 + 1);
+            status += &if self.pasting {
+                let hints = self.keymap.hints(&[
+                    (Action::Paste, "paste"),
+                    (Action::Mirror, "mirror"),
+                    (Action::Rotate, "rotate"),
+                    (Action::Select, "cancel"),
+                ]);
+                format!("\nPasting {size}: {hints}")
+            } else {
+                let hints = self.keymap.hints(&[
+                    (Action::Copy, "copy"),
+                    (Action::Cut, "cut"),
+                    (Action::Select, "cancel"),
+                ]);
+                format!("\nSelecting {size}: {hints}")
+            };
+        }
+        if let Some(issue) = self.issues.first() {
+            status += &format!("\nIssues: {}, first: {issue}", self.issues.len());
+        }
+        if let Some(confirm) = self.confirm {
+            let what = match confirm {
+                Action::Quit => "quit anyway",
+                _ => "switch anyway",
+            };
+            let hints = self
+                .keymap
+                .hints(&[(confirm, what), (Action::Save, "save")]);
+            status += &format!("\nUnsaved changes: {hints}");
+        }
+        if let Some(warning) = &self.warning {
+            status += &format!("\n{warning}");
+        }
+        match &self.prompt {
+            Some(Prompt::SaveAs(text)) => {
+                status += &format!("\nSave as: {text}_ (Enter - save, Esc - cancel)");
+            }
+            Some(Prompt::Restore) => {
+                status += "\nUnsaved changes were autosaved: Y - restore, N - discard";
+            }
+            None => (),
+        }
+        status
     }
 }
 
 impl Editor {
-    pub fn new(args: &Arguments) -> io::Result<Self> {
+    fn get_level_path(&self) -> &str {
+        &self.level_paths[self.level_idx]
+    }
+
+    pub fn new(args: &Arguments) -> Result<Self> {
+        let (pack, level_paths) = Pack::from_args(args)?;
         let mut editor = Self {
-            file_name: args.level_paths[0].clone(),
+            level_paths,
+            pack_name: pack.map(|pack| pack.name),
+            keymap: Keymap::load(args, &ProgramMode::Editor)?,
+            game_keymap: Keymap::load(args, &ProgramMode::Game)?,
+            delay: args.delay,
+            pause: args.pause,
+            trim: args.trim,
+            new_size: args.new_size,
+            autosave: args.autosave,
             ..Default::default()
         };
         editor.reload()?;
+        editor.offer_recovery();
         Ok(editor)
     }
 
-    fn reload(&mut self) -> io::Result<()> {
+    fn reload(&mut self) -> Result<()> {
+        let (contents, exists) = match bdcff::read_level(self.get_level_path()) {
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                (new_level(self.new_size), false)
+            }
+            result => (result?, true),
+        };
+        self.load(&contents);
+
+        // A new level is unsaved until it's written
+        self.saved = if exists {
+            self.get_contents()
+        } else {
+            String::new()
+        };
+        Ok(())
+    }
+
+    // Every char is kept, saving an untouched level gives back the same bytes
+    fn load(&mut self, contents: &str) {
         self.matrix = vec![];
+        self.endings = vec![];
 
-        let contents = fs::read_to_string(&self.file_name)?;
-        for (y, line) in contents.trim().lines().map(str::trim).enumerate() {
-            self.matrix.push(line.chars().map(Object::new).collect());
-            self.damaged.extend((0..line.len()).map(|x| (x, y)));
+        self.issues = validate::validate(contents);
+        let (header, grid) = validate::split_header(contents);
+        self.header = header.to_string();
+        for (y, line) in grid.split_inclusive('\n').enumerate() {
+            let (line, ending) = match (line.strip_suffix("\r\n"), line.strip_suffix('\n')) {
+                (Some(line), _) => (line, "\r\n"),
+                (None, Some(line)) => (line, "\n"),
+                (None, None) => (line, ""),
+            };
+            self.endings.push(ending);
+            let row: Vec<Object> = line.chars().map(Object::new).collect();
+            self.damaged.extend((0..row.len()).map(|x| (x, y)));
+            self.matrix.push(row);
         }
+    }
 
-        if self.matrix.is_empty() {
-            self.matrix.push(vec![Object::default()]);
-            self.damaged.insert((0, 0));
+    // Autosaves are removed on save, one left over means the changes were never saved
+    fn recovery_path(&self) -> String {
+        format!("{}.recovery", bdcff::save_path(self.get_level_path()))
+    }
+
+    // An autosave of what's on disk has nothing to restore
+    fn offer_recovery(&mut self) {
+        match fs::read_to_string(self.recovery_path()) {
+            Ok(contents) if contents == self.saved => self.discard_recovery(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            _ => self.prompt = Some(Prompt::Restore),
+        }
+    }
+
+    fn discard_recovery(&mut self) {
+        let path = self.recovery_path();
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                let e = Error::io(path, e);
+                self.warning = Some(format!("Discarding the autosave failed: {e}"));
+            }
+            _ => (),
         }
+    }
+
+    // Edits undone back to the saved level leave nothing to recover,
+    // an autosave from before this run is left to the prompt
+    fn discard_stale_recovery(&mut self, autosaved: &mut String) {
+        if !self.is_dirty() && !autosaved.is_empty() {
+            self.discard_recovery();
+            autosaved.clear();
+        }
+    }
 
+    fn restore(&mut self) -> Result<()> {
+        let path = self.recovery_path();
+        let contents = fs::read_to_string(&path).map_err(|e| Error::io(&path, e))?;
+        self.checkpoint(); // Restoring can be undone too
+        self.damage_all();
+        self.load(&contents);
         Ok(())
     }
 
-    fn save(&mut self) -> io::Result<()> {
-        let mut contents = String::new();
+    fn is_dirty(&self) -> bool {
+        self.get_contents() != self.saved
+    }
+
+    // Remember the matrix before an edit, a whole pen stroke is one step
+    fn checkpoint(&mut self) {
+        self.undo.push(self.matrix.clone());
+        if self.undo.len() > HISTORY_LEN {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    fn undo(&mut self) {
+        if let Some(matrix) = self.undo.pop() {
+            self.redo.push(mem::replace(&mut self.matrix, matrix));
+            self.damage_all();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(matrix) = self.redo.pop() {
+            self.undo.push(mem::replace(&mut self.matrix, matrix));
+            self.damage_all();
+        }
+    }
+
+    // An undoable change to the size of the level
+    fn reshape(&mut self, change: impl FnOnce(&mut Vec<Vec<Object>>, Point)) {
+        self.checkpoint();
+        self.damage_all(); // Cells that are gone get cleared
+        change(&mut self.matrix, self.cursor);
+        if self.matrix.is_empty() {
+            self.matrix.push(vec![]);
+        }
+        self.damage_all();
+    }
+
+    fn damage_all(&mut self) {
+        for (y, row) in self.matrix.iter().enumerate() {
+            self.damaged.extend((0..row.len()).map(|x| (x, y)));
+        }
+    }
+
+    fn damage_rect(&mut self, ((x1, y1), (x2, y2)): (Point, Point)) {
+        for y in y1..=y2 {
+            self.damaged.extend((x1..=x2).map(|x| (x, y)));
+        }
+    }
+
+    fn get_block(&self, ((x1, y1), (x2, y2)): (Point, Point)) -> Vec<Vec<Object>> {
+        (y1..=y2)
+            .map(|y| {
+                (x1..=x2)
+                    .map(|x| self.matrix.get(y).and_then(|row| row.get(x)))
+                    .map(|obj| obj.cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn get_current_object(&self) -> Object {
+        Object::get_all_displayable().swap_remove(self.current_object)
+    }
+
+    // The level grows to fit the point
+    fn set(&mut self, (x, y): Point, obj: Object) {
+        if self.matrix.len() <= y {
+            self.matrix.resize(y + 1, vec![]);
+        }
+        let row = &mut self.matrix[y];
+        if row.len() <= x {
+            row.resize(x + 1, Object::default());
+        }
+        row[x] = obj;
+        self.damaged.insert((x, y));
+    }
+
+    // A stroke is one undo step, taken before the first cell it changes
+    fn paint(&mut self, (x, y): Point, obj: Object) {
+        if self.matrix.get(y).and_then(|row| row.get(x)) == Some(&obj) {
+            return;
+        }
+        if !self.stroke_saved {
+            self.checkpoint();
+            self.stroke_saved = true;
+        }
+        self.set((x, y), obj);
+    }
+
+    // The clipboard goes to the cursor
+    fn paste(&mut self) {
+        self.checkpoint();
+        let (x, y) = self.cursor;
+
+        let clipboard = mem::take(&mut self.clipboard);
+        for (dy, row) in clipboard.iter().enumerate() {
+            for (dx, obj) in row.iter().enumerate() {
+                self.set((x + dx, y + dy), obj.clone());
+            }
+        }
+        self.clipboard = clipboard;
+    }
+
+    // Replace the connected region of the same object under the cursor
+    fn fill(&mut self) {
+        let (x, y) = self.cursor;
+        let target = self.matrix.get(y).and_then(|row| row.get(x)).cloned();
+        let obj = self.get_current_object();
+        let Some(target) = target.filter(|target| *target != obj) else {
+            return;
+        };
+        self.checkpoint();
+
+        let mut queue = VecDeque::from([self.cursor]);
+        while let Some((x, y)) = queue.pop_front() {
+            if self.matrix.get(y).and_then(|row| row.get(x)) != Some(&target) {
+                continue;
+            }
+            self.set((x, y), obj.clone());
+            queue.extend([(x + 1, y), (x, y + 1)]);
+            if x > 0 {
+                queue.push_back((x - 1, y));
+            }
+            if y > 0 {
+                queue.push_back((x, y - 1));
+            }
+        }
+    }
+
+    // The cells a line or a rectangle from `start` to the cursor covers
+    fn get_shape(&self, start: Point) -> Vec<Point> {
+        let ((x1, y1), (x2, y2)) = span(start, self.cursor);
+        let cells = (y1..=y2).flat_map(|y| (x1..=x2).map(move |x| (x, y)));
+
+        match self.tool {
+            Tool::Line => line(start, self.cursor),
+            Tool::Rect => cells
+                .filter(|&(x, y)| x == x1 || x == x2 || y == y1 || y == y2)
+                .collect(),
+            Tool::FilledRect => cells.collect(),
+            Tool::Pen | Tool::Fill => vec![],
+        }
+    }
+
+    // The pen goes up or down, the others apply to the level
+    fn use_tool(&mut self) {
+        match (self.tool, self.tool_start) {
+            (Tool::Pen, _) => {
+                self.pen_down = !self.pen_down;
+                self.stroke_saved = false;
+            }
+            (Tool::Fill, _) => self.fill(),
+            (_, None) => self.tool_start = Some(self.cursor),
+            (_, Some(start)) => {
+                self.checkpoint();
+                let obj = self.get_current_object();
+                for point in self.get_shape(start) {
+                    self.set(point, obj.clone());
+                }
+                self.tool_start = None;
+            }
+        }
+    }
 
-        for row in &self.matrix {
-            contents += row.iter().map(Labels::char).collect::<String>().trim();
-            contents.push('\n');
+    fn mirror(&mut self) {
+        for row in &mut self.clipboard {
+            row.reverse();
         }
+    }
 
-        fs::write(&self.file_name, contents.trim())
+    // Clockwise
+    fn rotate(&mut self) {
+        let height = self.clipboard.len();
+        let width = self.clipboard.first().map_or(0, Vec::len);
+        self.clipboard = (0..width)
+            .map(|x| {
+                (0..height)
+                    .rev()
+                    .map(|y| self.clipboard[y][x].clone())
+                    .collect()
+            })
+            .collect();
     }
 
-    pub fn run(&mut self, interaction: &mut Mode) -> Result<(), Box<dyn Error>> {
+    // Put the paste preview over the matrix and damage what the highlight covered
+    fn refresh_view(&mut self) {
+        let (x, y) = self.cursor;
+        let highlight = match self.anchor.or(self.tool_start) {
+            Some(start) => Some(span(start, self.cursor)),
+            None if self.pasting && !self.clipboard.is_empty() => {
+                let width = self.clipboard[0].len();
+                Some(((x, y), (x + width - 1, y + self.clipboard.len() - 1)))
+            }
+            None => None,
+        };
+
+        for rect in [self.highlight, highlight].into_iter().flatten() {
+            self.damage_rect(rect);
+        }
+        self.highlight = highlight;
+
+        self.view.clone_from(&self.matrix);
+        // Past the end of a row the cursor is on void that isn't saved unless painted
+        if self.view.len() <= y {
+            self.view.resize(y + 1, vec![]);
+        }
+        if self.view[y].len() <= x {
+            self.view[y].resize(x + 1, Object::default());
+            self.damaged.insert((x, y));
+        }
+        if let Some(start) = self.tool_start {
+            let obj = self.get_current_object();
+            for (x, y) in self.get_shape(start) {
+                if let Some(cell) = self.view.get_mut(y).and_then(|r| r.get_mut(x)) {
+                    *cell = obj.clone();
+                }
+            }
+        } else if self.pasting && self.anchor.is_none() {
+            for (dy, row) in self.clipboard.iter().enumerate() {
+                for (dx, obj) in row.iter().enumerate() {
+                    if let Some(cell) = self.view.get_mut(y + dy).and_then(|r| r.get_mut(x + dx)) {
+                        *cell = obj.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    // Rows keep their loaded line ending, new ones get the first one of the file
+    fn get_contents(&self) -> String {
+        let default = self
+            .endings
+            .iter()
+            .find(|ending| !ending.is_empty())
+            .map_or("\n", |ending| ending);
+        let last = self.matrix.len().saturating_sub(1);
+
+        let mut contents = self.header.clone();
+        for (y, row) in self.matrix.iter().enumerate() {
+            contents.extend(row.iter().map(Labels::char));
+            contents += match self.endings.get(y) {
+                _ if y == last => self.endings.last().map_or("", |ending| ending),
+                Some(ending) if !ending.is_empty() => ending,
+                _ => default,
+            };
+        }
+        contents
+    }
+
+    // A failed save keeps the edits, the status tells why and saving can be tried again
+    fn save_or_warn(&mut self, path: String) {
+        if let Err(e) = self.save_to(path) {
+            self.warning = Some(format!("Save failed: {e}"));
+        }
+    }
+
+    // The level path only changes once the level is written
+    fn save_to(&mut self, path: String) -> Result<()> {
+        let contents = self.get_contents();
+        self.issues = validate::validate(&contents);
+
+        // Saved as another file, the autosave of the old one is left behind otherwise
+        let recovery = self.recovery_path();
+        // Imported caves are saved to a file of their own
+        let path = bdcff::save_path(&path);
+        // The previous version is kept as a backup
+        let backup = format!("{path}.bak");
+        match fs::copy(&path, &backup) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(Error::io(backup, e)),
+            _ => (),
+        }
+        write_atomic(&path, &contents)?;
+        self.level_paths[self.level_idx] = path;
+        self.saved = contents;
+        self.warning = None;
+
+        match fs::remove_file(&recovery) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(Error::io(recovery, e)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn run(&mut self, interaction: &mut (impl Interaction + ?Sized)) -> Result<()> {
+        self.refresh_view();
         interaction.draw(self)?;
 
         let objects = Object::get_all_displayable();
+        let mut last_autosave = Instant::now();
+        let mut autosaved = String::new();
 
         loop {
             thread::sleep(Duration::from_millis(25));
 
+            // Discarded changes stay in the recovery file until the next save
+            if self
+                .autosave
+                .is_some_and(|interval| last_autosave.elapsed() >= interval)
+            {
+                last_autosave = Instant::now();
+                let contents = self.get_contents();
+                // A failed write is retried once there are more changes
+                if self.is_dirty() && contents != autosaved {
+                    if let Err(e) = write_atomic(&self.recovery_path(), &contents) {
+                        self.warning = Some(format!("Autosave failed: {e}"));
+                        interaction.draw(self)?;
+                    }
+                    autosaved = contents;
+                } else {
+                    self.discard_stale_recovery(&mut autosaved);
+                }
+            }
+
+            if let Some(prompt) = &mut self.prompt {
+                let Some(chr) = interaction.get_char() else {
+                    continue;
+                };
+                match (prompt, chr) {
+                    (_, '\u{1b}') => self.prompt = None,
+                    // A failed save as keeps the old path
+                    (Prompt::SaveAs(text), '\n') => {
+                        let path = mem::take(text);
+                        self.prompt = None;
+                        if !path.is_empty() {
+                            self.save_or_warn(path);
+                        }
+                    }
+                    (Prompt::SaveAs(text), '\u{8}') => {
+                        text.pop();
+                    }
+                    (Prompt::SaveAs(text), chr) => text.push(chr),
+                    (Prompt::Restore, 'y' | 'Y') => {
+                        self.prompt = None;
+                        self.restore()?;
+                    }
+                    (Prompt::Restore, 'n' | 'N') => {
+                        self.prompt = None;
+                        self.discard_recovery();
+                    }
+                    (Prompt::Restore, _) => continue,
+                }
+                self.refresh_view();
+                interaction.draw(self)?;
+                continue;
+            }
+
             let mut direction = None;
 
-            let input = interaction.get_input();
-            match input {
-                Input::Quit | Input::Q => {
-                    self.save()?;
+            let action = interaction.get_action();
+            // Pressing the same key again confirms, anything else cancels
+            let confirmed = action != Action::Unknown && self.confirm.take() == Some(action);
+            match action {
+                // A single level has nothing to switch to, its history stays
+                Action::NextLevel if self.level_paths.len() == 1 => (),
+                Action::Quit | Action::NextLevel if self.is_dirty() && !confirmed => {
+                    self.confirm = Some(action);
+                    self.pen_down = false;
+                }
+                Action::Quit => {
+                    self.discard_stale_recovery(&mut autosaved);
                     return Ok(());
                 }
-                Input::R => {
+                Action::Restart => {
+                    self.checkpoint(); // Reloading can be undone too
+                    self.reload()?;
+                    self.pen_down = false;
+                }
+                Action::Undo => {
+                    self.pen_down = false;
+                    self.undo();
+                }
+                Action::Redo => {
+                    self.pen_down = false;
+                    self.redo();
+                }
+                Action::Save => self.save_or_warn(self.get_level_path().to_string()),
+                Action::SaveAs => {
+                    self.prompt = Some(Prompt::SaveAs(String::new()));
+                    self.pen_down = false;
+                }
+                // The game keys are bound while it runs, the editor ones come back after
+                Action::Playtest => {
+                    let contents = self.get_contents();
+                    self.issues = validate::validate(&contents);
+                    if self.issues.is_empty() {
+                        self.pen_down = false;
+                        let game_keymap = mem::take(&mut self.game_keymap);
+                        let editor_keymap = interaction.swap_keymap(game_keymap);
+                        let keymap = self.game_keymap.clone();
+                        let played = Game::playtest(&contents, self.delay, self.pause, keymap)
+                            .and_then(|mut game| game.run(interaction));
+                        self.game_keymap = interaction.swap_keymap(editor_keymap);
+                        played?;
+                        self.damage_all();
+                    }
+                }
+                Action::NextLevel => {
+                    self.discard_stale_recovery(&mut autosaved);
+                    self.level_idx = (self.level_idx + 1) % self.level_paths.len();
                     self.reload()?;
+                    self.cursor = (0, 0);
                     self.pen_down = false;
+                    self.undo.clear();
+                    self.redo.clear();
+                    self.offer_recovery();
+                    autosaved.clear();
                 }
-                Input::Esc => self.save()?,
-                Input::Space => {
-                    self.pen_down = !self.pen_down;
+                Action::Select => {
+                    self.anchor = match self.anchor {
+                        Some(_) => None,
+                        None if self.pasting => {
+                            self.pasting = false;
+                            None
+                        }
+                        None => Some(self.cursor),
+                    };
+                    self.pen_down = false;
+                }
+                Action::Copy | Action::Cut => {
+                    if let Some(anchor) = self.anchor.take() {
+                        let rect = span(anchor, self.cursor);
+                        self.clipboard = self.get_block(rect);
+                        if action == Action::Cut {
+                            self.checkpoint();
+                            let ((x1, y1), (x2, y2)) = rect;
+                            for row in self.matrix.iter_mut().take(y2 + 1).skip(y1) {
+                                for obj in row.iter_mut().take(x2 + 1).skip(x1) {
+                                    *obj = Object::default();
+                                }
+                            }
+                        }
+                        self.pasting = true;
+                    }
                 }
-                Input::Comma => {
+                // Paste shows the preview first if it's hidden
+                Action::Paste if !self.clipboard.is_empty() => {
+                    if self.pasting {
+                        self.paste();
+                    }
+                    self.pasting = !self.pasting;
+                }
+                Action::Mirror if !self.clipboard.is_empty() => {
+                    self.mirror();
+                    self.pasting = true;
+                }
+                Action::Rotate if !self.clipboard.is_empty() => {
+                    self.rotate();
+                    self.pasting = true;
+                }
+                Action::UseTool => self.use_tool(),
+                Action::NextTool => {
+                    self.tool = self.tool.next();
+                    self.tool_start = None;
+                    self.pen_down = false;
+                }
+                Action::PrevPalette => {
                     if self.current_object == 0 {
                         self.current_object = objects.len();
                     }
                     self.current_object -= 1;
                 }
-                Input::Period => {
+                Action::NextPalette => {
                     self.current_object += 1;
                     if self.current_object >= objects.len() {
                         self.current_object = 0;
                     }
                 }
 
-                Input::Up
-                | Input::Down
-                | Input::Left
-                | Input::Right
-                | Input::W
-                | Input::A
-                | Input::S
-                | Input::D => direction = Direction::try_from(input).ok(),
+                Action::InsertRow => self.reshape(|matrix, (_, y)| {
+                    let width = matrix.get(y).map_or(0, Vec::len);
+                    matrix.insert(y.min(matrix.len()), vec![Object::default(); width]);
+                }),
+                Action::DeleteRow => self.reshape(|matrix, (_, y)| {
+                    if y < matrix.len() {
+                        matrix.remove(y);
+                    }
+                }),
+                Action::InsertColumn => self.reshape(|matrix, (x, _)| {
+                    for row in matrix.iter_mut().filter(|row| x <= row.len()) {
+                        row.insert(x, Object::default());
+                    }
+                }),
+                Action::DeleteColumn => self.reshape(|matrix, (x, _)| {
+                    for row in matrix.iter_mut().filter(|row| x < row.len()) {
+                        row.remove(x);
+                    }
+                }),
+                // The cursor becomes the bottom right corner
+                Action::ResizeToCursor => self.reshape(|matrix, (x, y)| {
+                    matrix.resize(y + 1, vec![]);
+                    for row in matrix {
+                        row.resize(x + 1, Object::default());
+                    }
+                }),
+                Action::WrapBorder => {
+                    self.reshape(|matrix, _| {
+                        let width = matrix.iter().map(Vec::len).max().unwrap_or(0) + 2;
+                        for row in matrix.iter_mut() {
+                            row.resize(width - 2, Object::default());
+                            row.insert(0, Wall.into());
+                            row.push(Wall.into());
+                        }
+                        matrix.insert(0, vec![Wall.into(); width]);
+                        matrix.push(vec![Wall.into(); width]);
+                    });
+                    self.cursor = (self.cursor.0 + 1, self.cursor.1 + 1);
+                }
+
+                Action::Move(dir) => direction = Some(dir),
 
-                Input::Unknown => continue,
+                // A click starts a stroke, drags continue it with lines until the release
+                Action::Click(point) | Action::Drag(point) if self.get_object(point).is_some() => {
+                    let from = match (action, self.last_drag) {
+                        (Action::Drag(_), Some(last)) => last,
+                        _ => {
+                            self.stroke_saved = false;
+                            point
+                        }
+                    };
+                    let obj = self.get_current_object();
+                    for point in line(from, point) {
+                        self.paint(point, obj.clone());
+                    }
+                    self.last_drag = Some(point);
+                    self.damaged.insert(self.cursor);
+                    self.cursor = point;
+                }
+                Action::Click(_) => self.last_drag = None,
+                Action::Release => {
+                    self.last_drag = None;
+                    self.stroke_saved = false;
+                }
+                Action::SelectObject(idx) if idx < objects.len() => self.current_object = idx,
+                Action::Pick((x, y)) => {
+                    let picked = self.matrix.get(y).and_then(|row| row.get(x));
+                    if let Some(idx) = picked.and_then(|obj| objects.iter().position(|o| o == obj))
+                    {
+                        self.current_object = idx;
+                    }
+                }
+
+                _ => continue,
             }
 
             if let Some(dir) = direction {
                 match dir {
-                    Direction::Up => {
+                    _ if !self.trim => (),
+                    Direction::Up
                         if self.matrix.len() > 1
                             && self
                                 .matrix
                                 .last()
-                                .map_or(false, |l| l.iter().all(|o| *o == Object::default()))
-                        {
-                            self.matrix.pop();
-                        }
+                                .is_some_and(|l| l.iter().all(|o| *o == Object::default())) =>
+                    {
+                        self.matrix.pop();
                     }
                     Direction::Left => {
                         for row in &mut self.matrix {
@@ -161,22 +937,11 @@ impl Editor {
                 self.cursor = dir.apply_to(&self.cursor);
             }
 
-            let (x, y) = self.cursor;
-
-            while y + 1 > self.matrix.len() {
-                self.matrix.push(vec![]);
-            }
-            // Moving up or down on a shorter row
-            while x + 1 > self.matrix[y].len() {
-                self.matrix[y].push(Object::default());
-                self.damaged.insert((self.matrix[y].len() - 1, y));
-            }
-
             if self.pen_down {
-                self.matrix[self.cursor.1][self.cursor.0] = objects[self.current_object].clone();
-                self.damaged.insert(self.cursor);
+                self.paint(self.cursor, objects[self.current_object].clone());
             }
 
+            self.refresh_view();
             interaction.draw(self)?;
         }
     }
@@ -0,0 +1,101 @@
+use crate::validate::ValidationError;
+use std::{error, fmt, io, result};
+
+pub type Result<T, E = Error> = result::Result<T, E>;
+
+pub(crate) type Source = Box<dyn error::Error + Send + Sync>;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    // Invalid command line arguments
+    Args(String),
+    // Reading or writing a file failed
+    Io {
+        path: String,
+        source: io::Error,
+    },
+    // A level didn't pass the validation
+    Level {
+        path: String,
+        source: ValidationError,
+    },
+    // A malformed BDCFF, pack, save, score, keys or config file,
+    // with the underlying parse error if there is one
+    Format {
+        path: String,
+        message: String,
+        source: Option<Source>,
+    },
+    // `--check` found levels with issues
+    Check {
+        invalid: usize,
+        total: usize,
+    },
+    // Sprites or fonts couldn't be loaded
+    Asset {
+        path: String,
+        source: Source,
+    },
+    // The interaction backend failed to initialize or draw
+    Render(Source),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Args(message) => write!(f, "{message}"),
+            Self::Io { path, .. } => write!(f, "Can't access `{path}`"),
+            Self::Level { path, .. } => write!(f, "The level `{path}` is invalid"),
+            Self::Format { path, message, .. } => write!(f, "`{path}`: {message}"),
+            Self::Check { invalid, total } => write!(f, "{invalid} of {total} levels have issues!"),
+            Self::Asset { path, .. } => write!(f, "Can't load the asset `{path}`"),
+            Self::Render(_) => write!(f, "Rendering failed"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Level { source, .. } => Some(source),
+            Self::Asset { source, .. } | Self::Render(source) => Some(source.as_ref()),
+            Self::Format { source, .. } => source.as_deref().map(|source| source as _),
+            Self::Args(_) | Self::Check { .. } => None,
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn io(path: impl Into<String>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn format(path: impl Into<String>, message: impl ToString) -> Self {
+        Self::Format {
+            path: path.into(),
+            message: message.to_string(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn parse(
+        path: impl Into<String>,
+        message: impl ToString,
+        source: impl Into<Source>,
+    ) -> Self {
+        Self::Format {
+            path: path.into(),
+            message: message.to_string(),
+            source: Some(source.into()),
+        }
+    }
+
+    pub(crate) fn render(source: impl Into<Source>) -> Self {
+        Self::Render(source.into())
+    }
+}
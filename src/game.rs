@@ -1,26 +1,45 @@
 use crate::{
-    args::Arguments,
+    args::{Arguments, ProgramMode},
+    bdcff,
     direction::Direction,
-    interaction::{Drawable, Input, Interaction, Mode},
+    error::{Error, Result},
+    interaction::{Action, Drawable, Interaction},
+    keys::Keymap,
     objects::Object,
+    pack::Pack,
+    scores::{self, Scores},
     Point,
 };
 use std::{
-    error::Error,
-    fs, io, thread,
+    path::Path,
+    thread,
     time::{Duration, Instant},
 };
 
+const PLAYTEST_NAME: &str = "(playtest)";
+
 pub mod level;
+mod menu;
+mod save;
 use level::{Level, State};
+use menu::{Entry, Menu};
 
 #[derive(Default)]
 pub struct Game {
     pause: bool,
+    paused_on_start: bool,
     delay: Duration,
+    elapsed: Duration, // time since the last tick, restored on resume
+    played: Duration,  // time spent ticking the current level
     level_idx: usize,
     levels: Vec<Level>,
     level_paths: Vec<String>,
+    level_hashes: Vec<u64>,
+    pack_name: Option<String>,
+    resume_path: Option<String>,
+    scores: Scores,
+    keymap: Keymap,           // for the key hints in the status
+    playtest: Option<String>, // contents of an unsaved level from the editor
 }
 
 impl Drawable for Game {
@@ -35,17 +54,41 @@ impl Drawable for Game {
     }
 
     fn get_status(&self) -> String {
-        match self.get_level().get_state() {
-            Some(State::Win) => "You have won!".to_string(),
-            Some(State::Lose) => "You have lost!\nR - reload".to_string(),
-            None => format!(
-                "Score: {}/{}\nDelay: {}ms\nPaused: {}",
-                self.get_level().get_score(),
-                self.get_level().get_max_score(),
-                self.delay.as_millis(),
-                if self.pause { "yes" } else { "no" }
-            ),
+        let mut status = match self.get_level().get_state() {
+            Some(State::Win) => match self.scores.get(self.level_hashes[self.level_idx]) {
+                Some(record) => format!("You have won!\n{record}"),
+                None => "You have won!".to_string(),
+            },
+            Some(State::Lose) => {
+                let hints = self.keymap.hints(&[(Action::Restart, "reload")]);
+                format!("You have lost!\n{hints}")
+            }
+            None => {
+                let mut status = format!(
+                    "Score: {}/{}\nDelay: {}ms\nPaused: {}",
+                    self.get_level().get_score(),
+                    self.get_level().get_max_score(),
+                    self.delay.as_millis(),
+                    if self.pause { "yes" } else { "no" }
+                );
+                if let Some(name) = &self.pack_name {
+                    status += &format!(
+                        "\n{name}: level {}/{}",
+                        self.level_idx + 1,
+                        self.levels.len()
+                    );
+                }
+                if self.levels.len() > 1 {
+                    let hints = self.keymap.hints(&[(Action::LevelSelect, "level select")]);
+                    status += &format!("\n{hints}");
+                }
+                status
+            }
+        };
+        if let Some(warning) = self.scores.get_warning() {
+            status += &format!("\n{warning}");
         }
+        status
     }
 }
 
@@ -60,91 +103,251 @@ impl Game {
         &self.level_paths[self.level_idx]
     }
 
-    pub fn new(args: &Arguments) -> io::Result<Self> {
+    pub fn new(args: &Arguments) -> Result<Self> {
+        if let Some(path) = &args.resume_path {
+            if Path::new(path).exists() {
+                return Ok(Self {
+                    resume_path: Some(path.clone()),
+                    scores: Scores::load_or_warn(),
+                    keymap: Keymap::load(args, &ProgramMode::Game)?,
+                    ..Self::load(path)?
+                });
+            }
+        }
+
+        let (pack, level_paths) = Pack::from_args(args)?;
         let mut game = Self {
             pause: args.pause,
+            paused_on_start: true,
             delay: args.delay,
-            level_paths: args.level_paths.clone(),
+            pack_name: pack.map(|pack| pack.name),
+            resume_path: args.resume_path.clone(),
+            scores: Scores::load_or_warn(),
+            keymap: Keymap::load(args, &ProgramMode::Game)?,
             ..Default::default()
         };
 
-        for path in &args.level_paths {
-            game.levels.push(Level::new(&fs::read_to_string(path)?));
+        for path in level_paths {
+            let contents = bdcff::read_level(&path)?;
+            let level = Level::new(&contents).map_err(|source| Error::Level {
+                path: path.clone(),
+                source,
+            })?;
+            game.levels.push(level);
+            game.level_hashes.push(scores::hash(&contents));
+            game.level_paths.push(path);
+        }
+
+        if game.levels.is_empty() {
+            return Err(Error::Args(
+                "Specify a level path with `-l some/path`!".into(),
+            ));
         }
 
         Ok(game)
     }
 
-    pub fn run(&mut self, interaction: &mut Mode) -> Result<(), Box<dyn Error>> {
+    // An unsaved level from the editor, its scores aren't recorded
+    pub fn playtest(contents: &str, delay: Duration, pause: bool, keymap: Keymap) -> Result<Self> {
+        let level = Level::new(contents).map_err(|source| Error::Level {
+            path: PLAYTEST_NAME.to_string(),
+            source,
+        })?;
+
+        Ok(Self {
+            pause,
+            paused_on_start: true,
+            delay,
+            levels: vec![level],
+            level_paths: vec![PLAYTEST_NAME.to_string()],
+            level_hashes: vec![scores::hash(contents)],
+            keymap,
+            playtest: Some(contents.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn is_completed(&self, idx: usize) -> bool {
+        self.scores
+            .get(self.level_hashes[idx])
+            .is_some_and(|record| record.ticks.is_some())
+    }
+
+    fn reload_level(&mut self) -> Result<()> {
+        let contents = match &self.playtest {
+            Some(contents) => contents.clone(),
+            None => bdcff::read_level(self.get_level_path())?,
+        };
+        self.levels[self.level_idx] = Level::new(&contents).map_err(|source| Error::Level {
+            path: self.get_level_path().to_string(),
+            source,
+        })?;
+        self.level_hashes[self.level_idx] = scores::hash(&contents);
+        self.played = Duration::ZERO;
+        self.paused_on_start = true;
+        Ok(())
+    }
+
+    fn save_on_quit(&self) -> Result<()> {
+        match &self.resume_path {
+            Some(path) => self.save(path),
+            None => Ok(()),
+        }
+    }
+
+    // A level is unlocked once the previous one is completed.
+    // Returns false if the player quit from the menu.
+    fn select_level(&mut self, interaction: &mut (impl Interaction + ?Sized)) -> Result<bool> {
+        let entries = (0..self.levels.len())
+            .map(|idx| Entry {
+                name: self.level_paths[idx].clone(),
+                completed: self.is_completed(idx),
+                locked: idx > 0 && !self.is_completed(idx - 1) && !self.is_completed(idx),
+            })
+            .collect();
+        let title = match &self.pack_name {
+            Some(name) => format!("{name}: select a level"),
+            None => "Select a level".to_string(),
+        };
+        let hints = self.keymap.hints(&[
+            (Action::TogglePause, "play"),
+            (Action::Back, "back"),
+            (Action::Quit, "quit"),
+        ]);
+        let mut menu = Menu::new(title, hints, entries, self.level_idx);
+
+        interaction.draw(&mut menu)?;
+        loop {
+            thread::sleep(Duration::from_millis(10));
+
+            match interaction.get_action() {
+                Action::Quit => return Ok(false),
+                Action::Back | Action::LevelSelect => break,
+                // Space pauses in the game and plays in the menu
+                Action::TogglePause => {
+                    if let Some(idx) = menu.get_selected() {
+                        if idx != self.level_idx {
+                            self.level_idx = idx;
+                            self.played = Duration::ZERO;
+                        }
+                        break;
+                    }
+                }
+                Action::Move(Direction::Up) => menu.select_prev(),
+                Action::Move(Direction::Down) => menu.select_next(),
+                _ => continue,
+            }
+            interaction.draw(&mut menu)?;
+        }
+
+        if self.get_level().get_state().is_some() {
+            self.reload_level()?;
+        }
+        self.paused_on_start = true;
+        self.get_level_mut().damage_all();
+        Ok(true)
+    }
+
+    fn record_score(&mut self) {
+        if self.playtest.is_some() {
+            return;
+        }
+        let level = self.get_level();
+        let win =
+            (*level.get_state() == Some(State::Win)).then_some((*level.get_ticks(), self.played));
+        let (hash, score) = (self.level_hashes[self.level_idx], *level.get_score());
+
+        let name = self.get_level_path().to_string();
+        self.scores.submit(hash, &name, score, win);
+        self.scores.save_or_warn();
+    }
+
+    pub fn run(&mut self, interaction: &mut (impl Interaction + ?Sized)) -> Result<()> {
         let mut direction = None;
-        let mut paused_on_start = true;
-        let mut timer = Instant::now();
+        let mut timer = Instant::now()
+            .checked_sub(self.elapsed)
+            .unwrap_or_else(Instant::now);
 
+        if self.levels.len() > 1 && !self.select_level(interaction)? {
+            return self.save_on_quit();
+        }
         interaction.draw(self)?;
 
         loop {
             thread::sleep(Duration::from_millis(10));
 
-            let input = interaction.get_input();
-            match input {
-                Input::Quit | Input::Q => return Ok(()),
-                Input::Comma => {
-                    if self.delay.as_millis() >= 100 {
-                        self.delay -= Duration::from_millis(50);
-                    }
+            let action = interaction.get_action();
+            match action {
+                Action::Quit => {
+                    self.elapsed = timer.elapsed();
+                    return self.save_on_quit();
                 }
-                Input::Period => {
-                    if self.delay.as_millis() <= 950 {
-                        self.delay += Duration::from_millis(50);
-                    }
+                Action::SpeedUp if self.delay.as_millis() >= 100 => {
+                    self.delay -= Duration::from_millis(50);
                 }
-                Input::Esc | Input::Space => self.pause = !self.pause,
-                Input::R => {
-                    self.levels[self.level_idx] =
-                        Level::new(&fs::read_to_string(self.get_level_path())?);
+                Action::SlowDown if self.delay.as_millis() <= 950 => {
+                    self.delay += Duration::from_millis(50);
+                }
+                Action::Back | Action::TogglePause => self.pause = !self.pause,
+                Action::Restart => {
+                    self.reload_level()?;
                     direction = None;
-                    paused_on_start = true;
+                    interaction.draw(self)?;
+                    continue;
+                }
+                Action::LevelSelect if self.levels.len() > 1 => {
+                    self.elapsed = timer.elapsed();
+                    if !self.select_level(interaction)? {
+                        return self.save_on_quit();
+                    }
+                    direction = None;
+                    timer = Instant::now();
                     interaction.draw(self)?;
                     continue;
                 }
 
-                Input::Up
-                | Input::Down
-                | Input::Left
-                | Input::Right
-                | Input::W
-                | Input::A
-                | Input::S
-                | Input::D => direction = Direction::try_from(input.clone()).ok(),
+                Action::Move(dir) => direction = Some(dir),
 
-                Input::Unknown => (),
+                _ => (),
             }
 
             if let Some(state) = self.get_level().get_state() {
                 if *state == State::Win && self.level_idx + 1 < self.levels.len() {
+                    // Preselect the next level
                     self.level_idx += 1;
+                    self.played = Duration::ZERO;
+                    if !self.select_level(interaction)? {
+                        return self.save_on_quit();
+                    }
+                    direction = None;
+                    timer = Instant::now();
                     interaction.draw(self)?;
                 }
                 continue;
             }
 
             if timer.elapsed() < self.delay {
-                if input != Input::Unknown {
+                if action != Action::Unknown {
                     interaction.draw(self)?;
                 }
                 continue;
             }
 
+            let elapsed = timer.elapsed();
             timer = Instant::now();
 
-            if paused_on_start && direction.is_some() {
-                paused_on_start = false;
+            if self.paused_on_start && direction.is_some() {
+                self.paused_on_start = false;
             }
-            if (self.pause && direction.is_none()) || paused_on_start {
+            if (self.pause && direction.is_none()) || self.paused_on_start {
                 continue;
             }
 
+            self.played += elapsed;
             self.get_level_mut().tick(direction.take());
+            if self.get_level().get_state().is_some() {
+                self.record_score();
+            }
             interaction.draw(self)?;
         }
     }
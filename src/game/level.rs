@@ -1,16 +1,18 @@
 use crate::{
     direction::Direction,
     objects::{Behaviour, Object, Properties},
+    validate::{self, ValidationError},
     Point,
 };
 use std::collections::HashSet;
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum State {
     Win,
     Lose,
 }
 
+#[derive(Debug)]
 pub enum Request {
     AddScore,
     AddMaxScore,
@@ -22,6 +24,7 @@ pub enum Request {
 pub struct Level {
     score: usize,
     max_score: usize,
+    ticks: usize,
     player: Point,
     state: Option<State>,
     damaged: HashSet<Point>,
@@ -36,6 +39,9 @@ impl Level {
     pub const fn get_max_score(&self) -> &usize {
         &self.max_score
     }
+    pub const fn get_ticks(&self) -> &usize {
+        &self.ticks
+    }
     pub const fn get_state(&self) -> &Option<State> {
         &self.state
     }
@@ -45,6 +51,11 @@ impl Level {
     pub fn get_damaged(&mut self) -> HashSet<Point> {
         std::mem::take(&mut self.damaged)
     }
+    pub fn damage_all(&mut self) {
+        for (y, row) in self.matrix.iter().enumerate() {
+            self.damaged.extend((0..row.len()).map(|x| (x, y)));
+        }
+    }
     pub fn get_object(&self, (x, y): Point) -> &Object {
         &self.matrix[y][x]
     }
@@ -54,9 +65,15 @@ impl Level {
 }
 
 impl Level {
-    pub fn new(string: &str) -> Self {
+    pub fn new(string: &str) -> Result<Self, ValidationError> {
+        let issues = validate::validate(string);
+        if !issues.is_empty() {
+            return Err(ValidationError(issues));
+        }
+
+        let (_, grid) = validate::split_header(string);
         let mut level = Self::default();
-        for (y, line) in string.trim().lines().enumerate() {
+        for (y, line) in grid.trim().lines().enumerate() {
             let mut row = vec![];
 
             for (x, chr) in line.trim().chars().enumerate() {
@@ -72,6 +89,32 @@ impl Level {
             level.matrix.push(row);
         }
 
+        Ok(level)
+    }
+
+    pub fn restore(
+        matrix: Vec<Vec<Object>>,
+        (score, max_score): (usize, usize),
+        ticks: usize,
+        state: Option<State>,
+    ) -> Self {
+        let mut level = Self {
+            score,
+            max_score,
+            ticks,
+            state,
+            ..Default::default()
+        };
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, obj) in row.iter().enumerate() {
+                if obj.player() {
+                    level.player = (x, y);
+                }
+                level.damaged.insert((x, y));
+            }
+        }
+        level.matrix = matrix;
+
         level
     }
 
@@ -98,6 +141,8 @@ impl Level {
     }
 
     pub fn tick(&mut self, direction: Option<Direction>) {
+        self.ticks += 1;
+
         // Player
         let requests = self
             .get_object(self.player)
@@ -0,0 +1,71 @@
+use crate::{interaction::Drawable, objects::Object, Point};
+
+pub struct Entry {
+    pub name: String,
+    pub completed: bool,
+    pub locked: bool,
+}
+
+// Level select screen, drawn as a status without any objects
+pub struct Menu {
+    title: String,
+    hints: String, // `<key> - <what>` from the keymap
+    selected: usize,
+    entries: Vec<Entry>,
+    objects: Vec<Vec<Object>>,
+}
+
+impl Drawable for Menu {
+    fn get_damaged(&mut self) -> Vec<Point> {
+        vec![]
+    }
+    fn get_objects(&self) -> &Vec<Vec<Object>> {
+        &self.objects
+    }
+    fn get_object(&self, _: Point) -> Option<&Object> {
+        None
+    }
+
+    fn get_status(&self) -> String {
+        let mut status = self.title.clone();
+
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let marker = if idx == self.selected { '>' } else { ' ' };
+            let label = match (entry.completed, entry.locked) {
+                (true, _) => " (completed)",
+                (false, true) => " (locked)",
+                (false, false) => "",
+            };
+            status += &format!("\n{marker} {}. {}{label}", idx + 1, entry.name);
+        }
+
+        status + "\n" + &self.hints
+    }
+}
+
+impl Menu {
+    pub fn new(title: String, hints: String, entries: Vec<Entry>, selected: usize) -> Self {
+        Self {
+            title,
+            hints,
+            selected,
+            entries,
+            objects: vec![],
+        }
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        if self.entries[self.selected].locked {
+            None
+        } else {
+            Some(self.selected)
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.entries.len() - 1);
+    }
+}
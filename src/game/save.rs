@@ -0,0 +1,143 @@
+use super::{
+    level::{Level, State},
+    Game,
+};
+use crate::{
+    error::{Error, Result, Source},
+    objects::{Labels, Object},
+};
+use std::{fs, time::Duration};
+
+// Save file layout, one `key value` pair per line:
+//   delay <ms>, pause <yes/no>, paused_on_start <yes/no>, elapsed <ms>, played <ms>, current <idx>
+// followed by every level:
+//   level <path>, hash <hex>, score <score> <max_score>, ticks <n>, state <none/win/lose>,
+//   rows <n>, <n matrix rows>
+
+const fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn next_value<'a>(lines: &mut impl Iterator<Item = &'a str>, key: &str) -> Result<&'a str, String> {
+    lines
+        .next()
+        .and_then(|line| line.strip_prefix(key)?.strip_prefix(' '))
+        .ok_or_else(|| format!("Expected `{key}` in the save file!"))
+}
+
+fn parse_yes_no(value: &str) -> Result<bool, String> {
+    match value {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => Err(format!("Can't parse `{value}` as yes/no!")),
+    }
+}
+
+fn parse_state(value: &str) -> Result<Option<State>, String> {
+    match value {
+        "none" => Ok(None),
+        "win" => Ok(Some(State::Win)),
+        "lose" => Ok(Some(State::Lose)),
+        _ => Err(format!("Can't parse `{value}` as a level state!")),
+    }
+}
+
+impl Game {
+    pub fn save(&self, path: &str) -> Result<()> {
+        let mut contents = format!(
+            "delay {}\npause {}\npaused_on_start {}\nelapsed {}\nplayed {}\ncurrent {}\n",
+            self.delay.as_millis(),
+            yes_no(self.pause),
+            yes_no(self.paused_on_start),
+            self.elapsed.as_millis(),
+            self.played.as_millis(),
+            self.level_idx,
+        );
+
+        for ((level, level_path), hash) in self
+            .levels
+            .iter()
+            .zip(&self.level_paths)
+            .zip(&self.level_hashes)
+        {
+            let state = match level.get_state() {
+                None => "none",
+                Some(State::Win) => "win",
+                Some(State::Lose) => "lose",
+            };
+            contents += &format!(
+                "level {level_path}\nhash {hash:016x}\nscore {} {}\nticks {}\nstate {state}\nrows {}\n",
+                level.get_score(),
+                level.get_max_score(),
+                level.get_ticks(),
+                level.get_objects().len(),
+            );
+            for row in level.get_objects() {
+                contents.extend(row.iter().map(Labels::char));
+                contents.push('\n');
+            }
+        }
+
+        fs::write(path, contents).map_err(|e| Error::io(path, e))
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        Self::parse_save(&contents).map_err(|e| Error::parse(path, "Malformed save file!", e))
+    }
+
+    fn parse_save(contents: &str) -> Result<Self, Source> {
+        let mut lines = contents.lines();
+
+        let mut game = Self {
+            delay: Duration::from_millis(next_value(&mut lines, "delay")?.parse()?),
+            pause: parse_yes_no(next_value(&mut lines, "pause")?)?,
+            paused_on_start: parse_yes_no(next_value(&mut lines, "paused_on_start")?)?,
+            elapsed: Duration::from_millis(next_value(&mut lines, "elapsed")?.parse()?),
+            played: Duration::from_millis(next_value(&mut lines, "played")?.parse()?),
+            level_idx: next_value(&mut lines, "current")?.parse()?,
+            ..Default::default()
+        };
+
+        while let Some(line) = lines.next() {
+            let level_path = line
+                .strip_prefix("level ")
+                .ok_or("Expected `level` in the save file!")?;
+            let hash = u64::from_str_radix(next_value(&mut lines, "hash")?, 16)?;
+            let (score, max_score) = next_value(&mut lines, "score")?
+                .split_once(' ')
+                .ok_or("Expected `score <score> <max_score>` in the save file!")?;
+            let ticks = next_value(&mut lines, "ticks")?.parse()?;
+            let state = parse_state(next_value(&mut lines, "state")?)?;
+            let rows = next_value(&mut lines, "rows")?.parse()?;
+
+            let matrix: Vec<Vec<Object>> = lines
+                .by_ref()
+                .take(rows)
+                .map(|line| line.chars().map(Object::new).collect())
+                .collect();
+            if matrix.len() != rows {
+                return Err(format!("Level `{level_path}` is cut short in the save file!").into());
+            }
+
+            game.levels.push(Level::restore(
+                matrix,
+                (score.parse()?, max_score.parse()?),
+                ticks,
+                state,
+            ));
+            game.level_paths.push(level_path.to_string());
+            game.level_hashes.push(hash);
+        }
+
+        if game.level_idx >= game.levels.len() {
+            return Err(format!("No level #{} in the save file!", game.level_idx).into());
+        }
+
+        Ok(game)
+    }
+}
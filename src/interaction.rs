@@ -1,40 +1,117 @@
 use crate::{
     args::{Arguments, InteractionMode},
-    objects::Object,
+    direction::Direction,
+    error::Result,
+    keys::Keymap,
+    objects::{Labels, Object},
     Point,
 };
 use enum_dispatch::enum_dispatch;
-use std::{cmp, error::Error};
+use std::{cmp, str::FromStr};
 
 mod cli;
 mod gui;
+mod script;
 mod tui;
 
-use cli::Cli;
-use gui::Gui;
-use tui::Tui;
+pub use cli::Cli;
+pub use gui::Gui;
+pub use script::Script;
+pub use tui::Tui;
 
-#[derive(Clone, PartialEq, Eq)]
-pub enum Input {
+// What the user wants to do, the backends resolve keys into actions with a `Keymap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    Unknown, // Nothing or an unbound key was pressed
     Quit,
-    Esc,
-    Unknown,
-    Q,
-    R,
-    W,
-    A,
-    S,
-    D,
+    Move(Direction),
 
-    Up,
-    Down,
-    Left,
-    Right,
-    Space,
-    Comma,
-    Period,
+    // Game and level select menu
+    Back,
+    TogglePause,
+    SpeedUp,
+    SlowDown,
+    Restart,
+    LevelSelect,
+
+    // Editor
+    Save,
+    UseTool,
+    NextTool,
+    PrevPalette,
+    NextPalette,
+    NextLevel,
+    Undo,
+    Redo,
+    Select,
+    Copy,
+    Cut,
+    Paste,
+    Mirror,
+    Rotate,
+    Playtest,
+    InsertRow,
+    DeleteRow,
+    InsertColumn,
+    DeleteColumn,
+    ResizeToCursor,
+    WrapBorder,
+    SaveAs,
+
+    // Mouse, with the cell under the pointer or the palette entry.
+    // These can't be bound to keys.
+    Click(Point),
+    Drag(Point),
+    Release, // the left button, ends a stroke
+    Pick(Point),
+    SelectObject(usize),
+}
+
+impl FromStr for Action {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unknown" => Ok(Self::Unknown),
+            "quit" => Ok(Self::Quit),
+            "up" => Ok(Self::Move(Direction::Up)),
+            "down" => Ok(Self::Move(Direction::Down)),
+            "left" => Ok(Self::Move(Direction::Left)),
+            "right" => Ok(Self::Move(Direction::Right)),
+            "back" => Ok(Self::Back),
+            "toggle_pause" => Ok(Self::TogglePause),
+            "speed_up" => Ok(Self::SpeedUp),
+            "slow_down" => Ok(Self::SlowDown),
+            "restart" => Ok(Self::Restart),
+            "level_select" => Ok(Self::LevelSelect),
+            "save" => Ok(Self::Save),
+            "use_tool" => Ok(Self::UseTool),
+            "next_tool" => Ok(Self::NextTool),
+            "prev_palette" => Ok(Self::PrevPalette),
+            "next_palette" => Ok(Self::NextPalette),
+            "next_level" => Ok(Self::NextLevel),
+            "undo" => Ok(Self::Undo),
+            "redo" => Ok(Self::Redo),
+            "select" => Ok(Self::Select),
+            "copy" => Ok(Self::Copy),
+            "cut" => Ok(Self::Cut),
+            "paste" => Ok(Self::Paste),
+            "mirror" => Ok(Self::Mirror),
+            "rotate" => Ok(Self::Rotate),
+            "playtest" => Ok(Self::Playtest),
+            "insert_row" => Ok(Self::InsertRow),
+            "delete_row" => Ok(Self::DeleteRow),
+            "insert_column" => Ok(Self::InsertColumn),
+            "delete_column" => Ok(Self::DeleteColumn),
+            "resize_to_cursor" => Ok(Self::ResizeToCursor),
+            "wrap_border" => Ok(Self::WrapBorder),
+            "save_as" => Ok(Self::SaveAs),
+            _ => Err(format!("Can't parse `{s}` as a valid action!")),
+        }
+    }
 }
 
+// The built-in backends
 #[enum_dispatch]
 pub enum Mode {
     Gui,
@@ -42,16 +119,54 @@ pub enum Mode {
     Cli,
 }
 
+// Implemented by the backends, other crates can plug in their own
 #[enum_dispatch(Mode)]
 pub trait Interaction {
-    fn get_input(&mut self) -> Input;
-    fn draw(&mut self, drawable: &mut impl Drawable) -> Result<(), Box<dyn Error>>;
+    fn get_action(&mut self) -> Action;
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()>;
+    // A typed char for text prompts, `None` until there is one. Enter submits with '\n',
+    // backspace is '\u{8}' and escape cancels with '\u{1b}', which is all the default gives.
+    fn get_char(&mut self) -> Option<char> {
+        Some('\u{1b}')
+    }
+    // Puts in the keys for another program mode and returns the ones it replaced,
+    // backends without a keymap give back what they got
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        keymap
+    }
+}
+
+impl<T: Interaction + ?Sized> Interaction for Box<T> {
+    fn get_action(&mut self) -> Action {
+        (**self).get_action()
+    }
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        (**self).draw(drawable)
+    }
+    fn get_char(&mut self) -> Option<char> {
+        (**self).get_char()
+    }
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        (**self).swap_keymap(keymap)
+    }
 }
 
 pub trait Drawable {
     fn get_cursor(&self) -> Option<&Point> {
         None
     }
+    // A rectangle for the backends to highlight, top left and bottom right corners
+    fn get_highlight(&self) -> Option<(Point, Point)> {
+        None
+    }
+    // Objects to choose from and the chosen one, the GUI shows them in a sidebar
+    fn get_palette(&self) -> Option<(Vec<Object>, usize)> {
+        None
+    }
+    fn is_highlighted(&self, (x, y): Point) -> bool {
+        self.get_highlight()
+            .is_some_and(|((x1, y1), (x2, y2))| (x1..=x2).contains(&x) && (y1..=y2).contains(&y))
+    }
     fn get_width(&self) -> usize {
         cmp::max(
             self.get_objects()
@@ -67,16 +182,27 @@ pub trait Drawable {
     fn get_height(&self) -> usize {
         self.get_objects().len() + self.get_status().lines().count()
     }
+    // Plain text snapshot: the grid as chars, an empty line and the status
+    fn get_frame(&self) -> String {
+        let mut frame = String::new();
+        for row in self.get_objects() {
+            frame.extend(row.iter().map(Labels::char));
+            frame.push('\n');
+        }
+        frame.push('\n');
+        frame + &self.get_status()
+    }
     fn get_status(&self) -> String;
     fn get_damaged(&mut self) -> Vec<Point>;
     fn get_objects(&self) -> &Vec<Vec<Object>>;
     fn get_object(&self, point: Point) -> Option<&Object>;
 }
 
-pub fn get_mode(args: &Arguments) -> Result<Mode, String> {
+pub fn get_mode(args: &Arguments) -> Result<Mode> {
+    let keymap = Keymap::load(args, &args.program_mode)?;
     Ok(match args.interaction_mode {
-        InteractionMode::Gui => Gui::new(args.size).map_err(|e| e.to_string())?.into(),
-        InteractionMode::Tui => Tui::new().into(),
-        InteractionMode::Cli => Cli::new().into(),
+        InteractionMode::Gui => Gui::new(args.size, args.theme.as_deref(), keymap)?.into(),
+        InteractionMode::Tui => Tui::new(keymap).into(),
+        InteractionMode::Cli => Cli::new(keymap).into(),
     })
 }
@@ -1,38 +1,35 @@
-use super::{Drawable, Input, Interaction, Tui};
-use crate::objects::Labels;
-use std::error::Error;
+use super::{Action, Drawable, Interaction, Tui};
+use crate::{
+    error::{Error, Result},
+    keys::Keymap,
+    objects::Labels,
+};
+use console::style;
+use std::io;
 
 pub struct Cli {
     tui: Tui,
 }
 
 impl Cli {
-    pub fn new() -> Self {
-        let tui = Tui::default();
+    pub fn new(keymap: Keymap) -> Self {
+        let tui = Tui::new(keymap);
         tui.get_term().clear_screen().unwrap();
 
         Self { tui }
     }
-}
-
-impl Default for Cli {
-    fn default() -> Self {
-        Self::new()
-    }
-}
 
-impl Interaction for Cli {
-    fn get_input(&mut self) -> Input {
-        self.tui.get_input()
-    }
-
-    fn draw(&mut self, drawable: &mut impl Drawable) -> Result<(), Box<dyn Error>> {
+    fn render(&mut self, drawable: &mut dyn Drawable) -> io::Result<()> {
         let term = self.tui.get_term();
 
         for (x, y) in drawable.get_damaged() {
             if let Some(obj) = drawable.get_object((x, y)) {
                 term.move_cursor_to(x, y)?;
-                term.write_line(&obj.char().to_string())?;
+                if drawable.is_highlighted((x, y)) {
+                    term.write_line(&style(obj.char()).reverse().to_string())?;
+                } else {
+                    term.write_line(&obj.char().to_string())?;
+                }
             }
         }
 
@@ -51,3 +48,27 @@ impl Interaction for Cli {
         Ok(())
     }
 }
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self::new(Keymap::default())
+    }
+}
+
+impl Interaction for Cli {
+    fn get_action(&mut self) -> Action {
+        self.tui.get_action()
+    }
+
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        self.render(drawable).map_err(Error::render)
+    }
+
+    fn get_char(&mut self) -> Option<char> {
+        self.tui.get_char()
+    }
+
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        self.tui.swap_keymap(keymap)
+    }
+}
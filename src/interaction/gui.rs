@@ -1,9 +1,15 @@
-use super::{Drawable, Input, Interaction};
-use crate::objects::Labels;
+use super::{Action, Drawable, Interaction};
+use crate::{
+    error::{Error, Result},
+    keys::Keymap,
+    objects::{Labels, Object, Unknown},
+    Point,
+};
 use sdl2::{
     event::Event,
     image::LoadTexture,
     keyboard::Keycode,
+    mouse::MouseButton,
     pixels::Color,
     rect::Rect,
     render::{Canvas, TextureCreator, TextureQuery},
@@ -11,7 +17,51 @@ use sdl2::{
     video::{Window, WindowContext},
     EventPump, IntegerOrSdlError,
 };
-use std::{collections::BTreeMap, error::Error, fs};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    error, fs, io, mem,
+    path::Path,
+};
+
+const FONT_PATH: &str = "assets/font.ttf";
+const SPRITES_PATH: &str = "assets/sprites/";
+
+type RenderResult = std::result::Result<(), Box<dyn error::Error + Send + Sync>>;
+
+fn asset_error(path: &str, e: io::Error) -> Error {
+    Error::Asset {
+        path: path.to_string(),
+        source: e.into(),
+    }
+}
+
+// Sprites by file name, a theme has to have one for every object
+fn load_sprites(sprites_path: &str) -> Result<BTreeMap<String, Box<[u8]>>> {
+    let mut sprites = BTreeMap::new();
+    let entries = fs::read_dir(sprites_path).map_err(|e| asset_error(sprites_path, e))?;
+    for path in entries.filter_map(std::result::Result::ok) {
+        let contents = fs::read(path.path())
+            .map_err(|e| asset_error(&path.path().to_string_lossy(), e))?
+            .into_boxed_slice();
+        sprites.insert(path.file_name().into_string().expect("str path"), contents);
+    }
+
+    let mut objects = Object::get_all_displayable();
+    objects.push(Unknown::new('?').into());
+    if let Some(name) = objects
+        .iter()
+        .map(Labels::name)
+        .find(|name| !sprites.contains_key(name))
+    {
+        let path = Path::new(sprites_path).join(name);
+        return Err(asset_error(
+            &path.to_string_lossy(),
+            io::ErrorKind::NotFound.into(),
+        ));
+    }
+
+    Ok(sprites)
+}
 
 pub struct Gui {
     scale: u32,
@@ -20,30 +70,36 @@ pub struct Gui {
     ttf_context: Sdl2TtfContext,
     texture_creator: TextureCreator<WindowContext>,
     texture_cache: BTreeMap<String, Box<[u8]>>,
+    keymap: Keymap,
+    pending: VecDeque<Action>,
+    palette: Option<(i32, usize)>, // sidebar x in pixels and the number of entries
 }
 
 impl Gui {
-    pub fn new(scale: u32) -> Result<Self, Box<dyn Error>> {
-        let sdl_context = sdl2::init()?;
-        let ttf_context = sdl2::ttf::init()?;
+    // `theme` is a directory with sprites named like the ones in `assets/sprites/`
+    pub fn new(scale: u32, theme: Option<&str>, keymap: Keymap) -> Result<Self> {
+        // Assets are checked before a window opens
+        let texture_cache = load_sprites(theme.unwrap_or(SPRITES_PATH))?;
+        // The font is loaded on every draw, fail early if it's missing
+        fs::metadata(FONT_PATH).map_err(|e| asset_error(FONT_PATH, e))?;
+
+        let sdl_context = sdl2::init().map_err(Error::render)?;
+        let ttf_context = sdl2::ttf::init().map_err(Error::render)?;
 
         let canvas = sdl_context
-            .video()?
+            .video()
+            .map_err(Error::render)?
             .window("Boulder Dash", 0, 0)
             .position_centered()
-            .build()?
+            .build()
+            .map_err(Error::render)?
             .into_canvas()
             .software()
-            .build()?;
-        let event_pump = sdl_context.event_pump()?;
+            .build()
+            .map_err(Error::render)?;
+        let event_pump = sdl_context.event_pump().map_err(Error::render)?;
         let texture_creator = canvas.texture_creator();
 
-        let mut texture_cache = BTreeMap::new();
-        for path in fs::read_dir("assets/sprites/")?.filter_map(Result::ok) {
-            let contents = fs::read(path.path())?.into_boxed_slice();
-            texture_cache.insert(path.file_name().into_string().expect("str path"), contents);
-        }
-
         Ok(Self {
             scale,
             canvas,
@@ -51,9 +107,25 @@ impl Gui {
             ttf_context,
             texture_creator,
             texture_cache,
+            keymap,
+            pending: VecDeque::new(),
+            palette: None,
         })
     }
 
+    fn get_cell(&self, x: i32, y: i32) -> Option<Point> {
+        let scale = usize::try_from(self.scale).ok()?;
+        Some((
+            usize::try_from(x).ok()? / scale,
+            usize::try_from(y).ok()? / scale,
+        ))
+    }
+
+    fn in_palette(&self, x: i32, idx: usize) -> bool {
+        self.palette
+            .is_some_and(|(left, len)| x >= left && idx < len)
+    }
+
     fn resize_window(&mut self, (width, height): (u32, u32)) -> Result<(), IntegerOrSdlError> {
         self.canvas.window_mut().set_minimum_size(width, height)?;
         self.canvas.window_mut().set_maximum_size(width, height)
@@ -63,53 +135,105 @@ impl Gui {
 }
 
 impl Interaction for Gui {
-    fn get_input(&mut self) -> Input {
-        let mut input = Input::Unknown;
-
+    // Events are queued so a click isn't lost to a following drag,
+    // consecutive drags are merged into the last one
+    fn get_action(&mut self) -> Action {
         while let Some(event) = self.event_pump.poll_event() {
-            input = match event {
-                Event::Quit { .. } => return Input::Quit,
+            let action = match event {
+                Event::Quit { .. } => Action::Quit,
 
                 Event::KeyDown {
                     keycode: Some(key), ..
-                } => match key {
-                    Keycode::Escape => Input::Esc,
-                    Keycode::Space => Input::Space,
-                    Keycode::Comma => Input::Comma,
-                    Keycode::Period => Input::Period,
-                    Keycode::Q => Input::Q,
-                    Keycode::P => Input::R,
-
-                    Keycode::W => Input::W,
-                    Keycode::A => Input::A,
-                    Keycode::R => Input::S,
-                    Keycode::S => Input::D,
-                    Keycode::Up => Input::Up,
-                    Keycode::Down => Input::Down,
-                    Keycode::Left => Input::Left,
-                    Keycode::Right => Input::Right,
-                    _ => input,
+                } => self.keymap.get(&key.name().to_lowercase()),
+
+                Event::MouseButtonDown {
+                    mouse_btn, x, y, ..
+                } => match (mouse_btn, self.get_cell(x, y)) {
+                    (MouseButton::Left, Some((_, idx))) if self.in_palette(x, idx) => {
+                        Action::SelectObject(idx)
+                    }
+                    (MouseButton::Left, Some(cell)) => Action::Click(cell),
+                    (MouseButton::Right, Some(cell)) => Action::Pick(cell),
+                    _ => Action::Unknown,
                 },
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                } => Action::Release,
+                Event::MouseMotion {
+                    mousestate, x, y, ..
+                } if mousestate.left() => self.get_cell(x, y).map_or(Action::Unknown, Action::Drag),
+                Event::MouseWheel { y, .. } if y > 0 => Action::PrevPalette,
+                Event::MouseWheel { y, .. } if y < 0 => Action::NextPalette,
+
+                _ => Action::Unknown,
+            };
+
+            match (self.pending.back_mut(), action) {
+                (_, Action::Unknown) => (),
+                (Some(last @ Action::Drag(_)), Action::Drag(_)) => *last = action,
+                _ => self.pending.push_back(action),
+            }
+        }
+
+        self.pending.pop_front().unwrap_or(Action::Unknown)
+    }
+
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        self.render(drawable).map_err(Error::Render)
+    }
 
-                _ => input,
+    // Printable chars come as text input, the rest as key presses
+    fn get_char(&mut self) -> Option<char> {
+        while let Some(event) = self.event_pump.poll_event() {
+            let chr = match event {
+                Event::TextInput { text, .. } => text.chars().next(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    ..
+                } => Some('\n'),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } => Some('\u{8}'),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                }
+                | Event::Quit { .. } => Some('\u{1b}'),
+                _ => None,
+            };
+            if chr.is_some() {
+                return chr;
             }
         }
+        None
+    }
 
-        input
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        mem::replace(&mut self.keymap, keymap)
     }
+}
 
-    fn draw(&mut self, drawable: &mut impl Drawable) -> Result<(), Box<dyn Error>> {
+impl Gui {
+    fn render(&mut self, drawable: &mut dyn Drawable) -> RenderResult {
         self.canvas.set_draw_color(Color::BLACK);
         // Redraw objects using the damaged buffer
         let mut objects_to_redraw = drawable.get_damaged();
 
         // WINDOW
 
+        let palette = drawable.get_palette();
+        let palette_len = palette.as_ref().map_or(0, |(objects, _)| objects.len());
+        let sidebar_width = if palette.is_some() { self.scale } else { 0 };
         let drawable_size = (
-            u32::try_from(drawable.get_width())? * self.scale,
+            u32::try_from(drawable.get_width())? * self.scale + sidebar_width,
             // scale + 1 is padding for the status
-            u32::try_from(drawable.get_height())? * (self.scale + 1),
+            (u32::try_from(drawable.get_height())? * (self.scale + 1))
+                .max(u32::try_from(palette_len)? * self.scale),
         );
+        let sidebar_left = i32::try_from(drawable_size.0 - sidebar_width)?;
+        self.palette = palette.is_some().then_some((sidebar_left, palette_len));
         if drawable_size != self.canvas.window().size() {
             // TODO: why it takes 2 calls to resize normally
             self.resize_window(drawable_size)?;
@@ -147,14 +271,14 @@ impl Interaction for Gui {
 
         let font = self
             .ttf_context
-            .load_font("assets/font.ttf", u16::try_from(self.scale)?)?;
+            .load_font(FONT_PATH, u16::try_from(self.scale)?)?;
         let mut level_bottom = u32::try_from(drawable.get_objects().len())? * self.scale;
 
         // Clear the bottom of the screen
         self.canvas.fill_rect(Rect::new(
             0,
             i32::try_from(level_bottom)?,
-            drawable_size.0,
+            drawable_size.0 - sidebar_width,
             drawable_size.1.saturating_sub(level_bottom),
         ))?;
 
@@ -172,6 +296,52 @@ impl Interaction for Gui {
             level_bottom += self.scale;
         }
 
+        // PALETTE
+
+        if let Some((objects, current)) = palette {
+            self.canvas.set_draw_color(Color::BLACK);
+            self.canvas
+                .fill_rect(Rect::new(sidebar_left, 0, sidebar_width, drawable_size.1))?;
+
+            let scale = i32::try_from(self.scale)?;
+            for (idx, obj) in objects.iter().enumerate() {
+                let rect = Rect::new(
+                    sidebar_left,
+                    i32::try_from(idx)? * scale,
+                    self.scale,
+                    self.scale,
+                );
+                let bytes = &self.texture_cache[&obj.name()];
+                let texture = self.texture_creator.load_texture_bytes(bytes)?;
+                self.canvas.copy(&texture, None, rect)?;
+            }
+
+            self.canvas.set_draw_color(Color::RGB(255, 200, 0));
+            for i in 0..self.scale / 10 + 1 {
+                self.canvas.draw_rect(Rect::new(
+                    sidebar_left + i32::try_from(i)?,
+                    i32::try_from(current)? * scale + i32::try_from(i)?,
+                    self.scale.saturating_sub(2 * i),
+                    self.scale.saturating_sub(2 * i),
+                ))?;
+            }
+        }
+
+        // HIGHLIGHT
+
+        if let Some(((x1, y1), (x2, y2))) = drawable.get_highlight() {
+            self.canvas.set_draw_color(Color::RGB(255, 200, 0));
+
+            for i in 0..self.scale / 10 + 1 {
+                self.canvas.draw_rect(Rect::new(
+                    i32::try_from(x1)? * i32::try_from(self.scale)? + i32::try_from(i)?,
+                    i32::try_from(y1)? * i32::try_from(self.scale)? + i32::try_from(i)?,
+                    (u32::try_from(x2 - x1 + 1)? * self.scale).saturating_sub(2 * i),
+                    (u32::try_from(y2 - y1 + 1)? * self.scale).saturating_sub(2 * i),
+                ))?;
+            }
+        }
+
         // CURSOR
 
         if let Some(&(x, y)) = drawable.get_cursor() {
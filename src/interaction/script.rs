@@ -0,0 +1,62 @@
+use super::{Action, Drawable, Interaction};
+use crate::error::{Error, Result};
+use std::{collections::VecDeque, fs};
+
+// Feeds a fixed queue of actions and captures every draw as a text frame.
+// `Quit` is returned once the queue runs out, prompts get escape once the text does.
+#[derive(Default)]
+pub struct Script {
+    actions: VecDeque<Action>,
+    text: VecDeque<char>,
+    frames: Vec<String>,
+}
+
+impl Script {
+    pub fn new(actions: impl IntoIterator<Item = Action>) -> Self {
+        Self {
+            actions: actions.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    // Chars typed into prompts, in order
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text.extend(text.chars());
+        self
+    }
+
+    // Whitespace separated action names, `#` starts a comment
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+
+        let actions = contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(str::split_whitespace)
+            .map(str::parse)
+            .collect::<Result<Vec<Action>, String>>()
+            .map_err(|e| Error::format(path, e))?;
+
+        Ok(Self::new(actions))
+    }
+
+    pub fn get_frames(&self) -> &[String] {
+        &self.frames
+    }
+}
+
+impl Interaction for Script {
+    fn get_action(&mut self) -> Action {
+        self.actions.pop_front().unwrap_or(Action::Quit)
+    }
+
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        drawable.get_damaged(); // Empty damaged buffer
+        self.frames.push(drawable.get_frame());
+        Ok(())
+    }
+
+    fn get_char(&mut self) -> Option<char> {
+        Some(self.text.pop_front().unwrap_or('\u{1b}'))
+    }
+}
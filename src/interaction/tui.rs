@@ -1,21 +1,26 @@
-use super::{Drawable, Input, Interaction};
-use crate::objects::Labels;
-use console::{Key, Term};
-use std::{error::Error, sync::mpsc, thread};
+use super::{Action, Drawable, Interaction};
+use crate::{
+    error::{Error, Result},
+    keys::Keymap,
+    objects::Labels,
+};
+use console::{style, Key, Term};
+use std::{io, mem, sync::mpsc, thread};
 
 pub struct Tui {
     term: Term,
     input_rx: mpsc::Receiver<Key>,
+    keymap: Keymap,
 }
 
 impl Default for Tui {
     fn default() -> Self {
-        Self::new()
+        Self::new(Keymap::default())
     }
 }
 
 impl Tui {
-    pub fn new() -> Self {
+    pub fn new(keymap: Keymap) -> Self {
         let (input_tx, input_rx) = mpsc::channel();
 
         let term = Term::stdout();
@@ -25,46 +30,29 @@ impl Tui {
             input_tx.send(key).expect("Receiver should be present");
         });
 
-        Self { term, input_rx }
+        Self {
+            term,
+            input_rx,
+            keymap,
+        }
     }
 
     pub const fn get_term(&self) -> &Term {
         &self.term
     }
-}
-
-impl Interaction for Tui {
-    fn get_input(&mut self) -> Input {
-        let input = self.input_rx.try_recv();
-        input.map_or(Input::Unknown, |key| match key {
-            Key::Escape => Input::Esc,
-            Key::Char(' ') => Input::Space,
-            Key::Char(',') => Input::Comma,
-            Key::Char('.') => Input::Period,
-            Key::Char('q') => Input::Q,
-            Key::Char('p') => Input::R,
-
-            Key::Char('w') => Input::W,
-            Key::Char('a') => Input::A,
-            Key::Char('r') => Input::S,
-            Key::Char('s') => Input::D,
-            Key::ArrowUp => Input::Up,
-            Key::ArrowDown => Input::Down,
-            Key::ArrowLeft => Input::Left,
-            Key::ArrowRight => Input::Right,
-
-            _ => Input::Unknown,
-        })
-    }
 
-    fn draw(&mut self, drawable: &mut impl Drawable) -> Result<(), Box<dyn Error>> {
+    fn render(&mut self, drawable: &mut dyn Drawable) -> io::Result<()> {
         self.term.clear_screen()?;
 
         drawable.get_damaged(); // Empty damaged buffer
-        for row in drawable.get_objects() {
+        for (y, row) in drawable.get_objects().iter().enumerate() {
             let mut line = String::new();
-            for obj in row {
-                line.push(obj.emoji());
+            for (x, obj) in row.iter().enumerate() {
+                if drawable.is_highlighted((x, y)) {
+                    line += &style(obj.emoji()).reverse().to_string();
+                } else {
+                    line.push(obj.emoji());
+                }
             }
             self.term.write_line(&line)?;
         }
@@ -82,3 +70,43 @@ impl Interaction for Tui {
         Ok(())
     }
 }
+
+impl Interaction for Tui {
+    fn get_action(&mut self) -> Action {
+        let Ok(key) = self.input_rx.try_recv() else {
+            return Action::Unknown;
+        };
+
+        // Same names as `Keycode::name` in the GUI, lowercased
+        let name = match key {
+            Key::Escape => "escape".to_string(),
+            Key::Tab => "tab".to_string(),
+            Key::Char(' ') => "space".to_string(),
+            Key::Char(chr) => chr.to_lowercase().to_string(),
+            Key::ArrowUp => "up".to_string(),
+            Key::ArrowDown => "down".to_string(),
+            Key::ArrowLeft => "left".to_string(),
+            Key::ArrowRight => "right".to_string(),
+            _ => return Action::Unknown,
+        };
+        self.keymap.get(&name)
+    }
+
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        self.render(drawable).map_err(Error::render)
+    }
+
+    fn get_char(&mut self) -> Option<char> {
+        match self.input_rx.try_recv().ok()? {
+            Key::Enter => Some('\n'),
+            Key::Backspace => Some('\u{8}'),
+            Key::Escape => Some('\u{1b}'),
+            Key::Char(chr) => Some(chr),
+            _ => None,
+        }
+    }
+
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        mem::replace(&mut self.keymap, keymap)
+    }
+}
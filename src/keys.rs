@@ -0,0 +1,185 @@
+use crate::{
+    args::{Arguments, ProgramMode},
+    direction::Direction,
+    dirs,
+    error::{Error, Result},
+    interaction::Action,
+};
+use std::{collections::HashMap, fs, io, str::FromStr};
+
+const KEYS_NAME: &str = "keys";
+
+// Built-in presets for the keys on the QWERTY positions of W, A, S, D and R
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Qwerty,
+    #[default]
+    Colemak,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "qwerty" => Ok(Self::Qwerty),
+            "colemak" => Ok(Self::Colemak),
+            _ => Err(format!("Can't parse `{s}` as a valid keyboard layout!")),
+        }
+    }
+}
+
+// Key names are lowercase: chars as is, `escape`, `tab`, `space` and the arrows
+// `up`, `down`, `left`, `right`. The backends resolve every pressed key here.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    keys: HashMap<String, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(Layout::default(), &ProgramMode::Game)
+    }
+}
+
+impl Keymap {
+    pub fn new(layout: Layout, program_mode: &ProgramMode) -> Self {
+        let (up, left, down, right, restart) = match layout {
+            Layout::Qwerty => ("w", "a", "s", "d", "r"),
+            Layout::Colemak => ("w", "a", "r", "s", "p"),
+        };
+        let moves = [
+            (up, Action::Move(Direction::Up)),
+            (left, Action::Move(Direction::Left)),
+            (down, Action::Move(Direction::Down)),
+            (right, Action::Move(Direction::Right)),
+            ("up", Action::Move(Direction::Up)),
+            ("left", Action::Move(Direction::Left)),
+            ("down", Action::Move(Direction::Down)),
+            ("right", Action::Move(Direction::Right)),
+            ("q", Action::Quit),
+        ];
+        let mode_keys = match program_mode {
+            ProgramMode::Game => vec![
+                ("escape", Action::Back),
+                ("space", Action::TogglePause),
+                ("tab", Action::LevelSelect),
+                (",", Action::SpeedUp),
+                (".", Action::SlowDown),
+                (restart, Action::Restart),
+            ],
+            ProgramMode::Editor => vec![
+                ("escape", Action::Save),
+                ("space", Action::UseTool),
+                ("f", Action::NextTool),
+                ("tab", Action::NextLevel),
+                (",", Action::PrevPalette),
+                (".", Action::NextPalette),
+                (restart, Action::Restart),
+                ("z", Action::Undo),
+                ("y", Action::Redo),
+                ("v", Action::Select),
+                ("c", Action::Copy),
+                ("x", Action::Cut),
+                ("b", Action::Paste),
+                ("m", Action::Mirror),
+                ("t", Action::Rotate),
+                ("g", Action::Playtest),
+                ("i", Action::InsertRow),
+                ("u", Action::DeleteRow),
+                ("o", Action::InsertColumn),
+                ("l", Action::DeleteColumn),
+                ("e", Action::ResizeToCursor),
+                ("h", Action::WrapBorder),
+                ("n", Action::SaveAs),
+            ],
+        };
+        let keys = moves
+            .into_iter()
+            .chain(mode_keys)
+            .map(|(key, action)| (key.to_string(), action))
+            .collect();
+
+        Self { keys }
+    }
+
+    // The `--layout` preset with the overrides from the keys file in the config directory
+    pub fn load(args: &Arguments, program_mode: &ProgramMode) -> Result<Self> {
+        let mut keymap = Self::new(args.layout, program_mode);
+
+        let path = dirs::config_dir().join(KEYS_NAME);
+        let path = path.to_string_lossy();
+        match fs::read_to_string(path.as_ref()) {
+            Ok(contents) => keymap
+                .apply(&contents, program_mode)
+                .map_err(|e| Error::format(path, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(Error::io(path, e)),
+        }
+
+        Ok(keymap)
+    }
+
+    // One `<key> <action>` pair per line, `#` starts a comment. Pairs after a `[game]`
+    // or `[editor]` header only apply to that program mode, the ones before to both.
+    // `<action>` is a name from `Action::from_str` or `none` to unbind the key.
+    pub fn apply(&mut self, contents: &str, program_mode: &ProgramMode) -> Result<(), String> {
+        let mut active = true;
+
+        for (idx, line) in contents.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line {
+                "[game]" => active = *program_mode == ProgramMode::Game,
+                "[editor]" => active = *program_mode == ProgramMode::Editor,
+                _ => {
+                    let Some((key, action)) = line.split_once(char::is_whitespace) else {
+                        return Err(format!("Unrecognized line {}: `{line}`!", idx + 1));
+                    };
+                    if !active {
+                        continue;
+                    }
+
+                    let key = key.to_lowercase();
+                    match action.trim() {
+                        "none" => self.keys.remove(&key),
+                        action => self.keys.insert(key, action.parse()?),
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Action {
+        self.keys.get(key).copied().unwrap_or(Action::Unknown)
+    }
+
+    // A key bound to `action` as shown in hints: `Esc`, `Tab`, `Space` or the uppercase char
+    pub fn hint(&self, action: Action) -> Option<String> {
+        let key = self
+            .keys
+            .iter()
+            .filter(|&(_, bound)| *bound == action)
+            .map(|(key, _)| key.as_str())
+            .min_by_key(|key| (key.len(), *key))?;
+
+        let mut chars = key.chars();
+        Some(match (key, chars.next()) {
+            ("escape", _) => "Esc".to_string(),
+            (_, Some(first)) => first.to_uppercase().chain(chars).collect(),
+            (_, None) => String::new(),
+        })
+    }
+
+    // `<key> - <what>` for the bound actions, after rebinds
+    pub fn hints(&self, hints: &[(Action, &str)]) -> String {
+        let hints: Vec<String> = hints
+            .iter()
+            .filter_map(|&(action, what)| Some(format!("{} - {what}", self.hint(action)?)))
+            .collect();
+        hints.join(", ")
+    }
+}
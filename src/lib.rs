@@ -1,23 +1,68 @@
+//! Boulder Dash engine: levels and their objects, the game and the level editor,
+//! driven by an `Interaction` backend that reads input and draws a `Drawable`.
+
 mod args;
+mod bdcff;
+mod config;
 mod direction;
+mod dirs;
 mod editor;
+mod error;
 mod game;
 mod interaction;
+mod keys;
 mod objects;
+mod pack;
+mod scores;
+mod validate;
+
+pub use args::{Arguments, Command, InteractionMode, ProgramMode, HELP_MSG, VERSION};
+pub use bdcff::read_level;
+pub use direction::Direction;
+pub use editor::Editor;
+pub use error::{Error, Result};
+pub use game::{
+    level::{Level, Request, State},
+    Game,
+};
+pub use interaction::{get_mode, Action, Cli, Drawable, Gui, Interaction, Mode, Script, Tui};
+pub use keys::{Keymap, Layout};
+pub use objects::{
+    Behaviour, Dirt, Gem, Labels, Object, Player, Properties, Rock, Unknown, Void, Wall,
+};
+pub use pack::Pack;
+pub use validate::{validate, Issue, Problem, ValidationError};
+
+use scores::Scores;
 
-pub use args::Arguments;
-use args::ProgramMode;
-use editor::Editor;
-use game::Game;
-use std::error::Error;
+pub type Point = (usize, usize); // (x, y)
 
-type Point = (usize, usize); // (x, y)
+pub fn run(args: &Arguments) -> Result<()> {
+    match run_report(args) {
+        Some(result) => result,
+        None => run_with(args, &mut get_mode(args)?),
+    }
+}
 
-pub fn run(args: &Arguments) -> Result<(), Box<dyn Error>> {
-    let mut mode = interaction::get_mode(args)?;
+// Same as `run`, but with a custom interaction backend instead of `args.interaction_mode`
+pub fn run_with(args: &Arguments, interaction: &mut (impl Interaction + ?Sized)) -> Result<()> {
+    if let Some(result) = run_report(args) {
+        return result;
+    }
 
     match args.program_mode {
-        ProgramMode::Game => Game::new(args)?.run(&mut mode),
-        ProgramMode::Editor => Editor::new(args)?.run(&mut mode),
+        ProgramMode::Game => Game::new(args)?.run(interaction),
+        ProgramMode::Editor => Editor::new(args)?.run(interaction),
+    }
+}
+
+// `--scores` and `--check` print a report instead of running, without a backend
+fn run_report(args: &Arguments) -> Option<Result<()>> {
+    if args.scores {
+        return Some(Scores::load().map(|scores| println!("{scores}")));
+    }
+    if args.check {
+        return Some(validate::check(args));
     }
+    None
 }
@@ -1,16 +1,29 @@
 use std::env;
+use std::error::Error;
 use std::process;
 
-use boulder_dash::Arguments;
+use boulder_dash::{Arguments, Command, HELP_MSG, VERSION};
 
 fn main() {
-    let config = Arguments::parse(env::args().skip(1)).unwrap_or_else(|err| {
+    let command = Arguments::parse(env::args().skip(1)).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {err}");
         process::exit(1);
     });
 
+    let config = match command {
+        Command::Run(config) => config,
+        Command::Help => return println!("{HELP_MSG}"),
+        Command::Version => return println!("boulder_dash {VERSION}"),
+    };
+
     if let Err(err) = boulder_dash::run(&config) {
-        eprintln!("Application error: {err}");
+        eprint!("Application error: {err}");
+        let mut source = err.source();
+        while let Some(err) = source {
+            eprint!(":\n{err}");
+            source = err.source();
+        }
+        eprintln!();
         process::exit(1);
     }
 }
@@ -13,16 +13,17 @@ mod unknown;
 mod void;
 mod wall;
 
-use dirt::Dirt;
-use gem::Gem;
-use player::Player;
-use rock::Rock;
-use unknown::Unknown;
-use void::Void;
-use wall::Wall;
+pub use dirt::Dirt;
+pub use gem::Gem;
+pub use player::Player;
+pub use rock::Rock;
+pub use unknown::Unknown;
+pub use void::Void;
+pub use wall::Wall;
 
 #[enum_dispatch]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Object {
     Gem,
     Wall,
@@ -59,7 +60,7 @@ impl Object {
             'O' => Rock.into(),
             ' ' => Void.into(),
             'p' => Player.into(),
-            _ => Unknown.into(),
+            _ => Unknown::new(chr).into(),
         }
     }
 }
@@ -1,15 +1,24 @@
 use super::{Behaviour, Labels, Properties};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Unknown;
+pub struct Unknown(char); // kept so the editor writes it back as is
+
+impl Unknown {
+    pub const fn new(chr: char) -> Self {
+        Self(chr)
+    }
+}
 
 impl Labels for Unknown {
     fn char(&self) -> char {
-        '?'
+        self.0
     }
     fn emoji(&self) -> char {
         '🯄'
     }
+    fn name(&self) -> String {
+        "unknown".to_string()
+    }
 }
 
 impl Properties for Unknown {
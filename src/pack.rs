@@ -0,0 +1,109 @@
+use crate::{
+    args::Arguments,
+    bdcff,
+    error::{Error, Result},
+};
+use std::{fs, path::Path};
+
+const MANIFEST_NAME: &str = "pack";
+
+// Manifest format, one `key value` pair per line, `#` starts a comment:
+//   name <string>, author <string>, description <string>, level <path>
+// Level paths are relative to the manifest, `level` keeps the order and can repeat.
+#[derive(Default)]
+pub struct Pack {
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub level_paths: Vec<String>,
+}
+
+impl Pack {
+    // `path` is either the manifest itself or a directory with a `pack` manifest
+    pub fn load(path: &str) -> Result<Self> {
+        let mut manifest = Path::new(path).to_path_buf();
+        if manifest.is_dir() {
+            manifest.push(MANIFEST_NAME);
+        }
+        let base = manifest.parent().unwrap_or(Path::new("")).to_path_buf();
+        let manifest = manifest.to_string_lossy().into_owned();
+
+        let contents = fs::read_to_string(&manifest).map_err(|e| Error::io(&manifest, e))?;
+
+        let mut pack = Self::default();
+        for (idx, line) in contents.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim().to_string();
+            match key {
+                "name" => pack.name = value,
+                "author" => pack.author = value,
+                "description" => pack.description = value,
+                "level" if !value.is_empty() => pack
+                    .level_paths
+                    .push(base.join(value).to_string_lossy().into_owned()),
+                _ => {
+                    let message = format!("Unrecognized line {}: `{line}`!", idx + 1);
+                    return Err(Error::format(manifest, message));
+                }
+            }
+        }
+
+        if pack.level_paths.is_empty() {
+            return Err(Error::format(manifest, "The pack has no levels!"));
+        }
+        if pack.name.is_empty() {
+            pack.name = path.to_string();
+        }
+
+        Ok(pack)
+    }
+
+    // The pack from `--pack` and all level paths, pack levels go first.
+    // BDCFF files are expanded into all of their caves.
+    // Paths from `-l` that don't exist are looked up in the configured level directories.
+    pub fn from_args(args: &Arguments) -> Result<(Option<Self>, Vec<String>)> {
+        let pack = args.pack_path.as_deref().map(Self::load).transpose()?;
+
+        let arg_paths = args
+            .level_paths
+            .iter()
+            .map(|path| find_level(path, &args.level_dirs));
+        let mut level_paths = vec![];
+        for path in pack
+            .iter()
+            .flat_map(|pack| pack.level_paths.iter().cloned())
+            .chain(arg_paths)
+        {
+            level_paths.extend(bdcff::expand_path(&path)?);
+        }
+
+        Ok((pack, level_paths))
+    }
+}
+
+fn find_level(path: &str, level_dirs: &[String]) -> String {
+    // A BDCFF cave `file#n` is found by its file
+    let exists = |path: &Path| {
+        path.exists()
+            || path
+                .to_str()
+                .and_then(|path| path.rsplit_once('#'))
+                .is_some_and(|(file, _)| Path::new(file).exists())
+    };
+
+    if exists(Path::new(path)) || Path::new(path).is_absolute() {
+        return path.to_string();
+    }
+    level_dirs
+        .iter()
+        .map(|dir| Path::new(dir).join(path))
+        .find(|candidate| exists(candidate))
+        .map_or_else(
+            || path.to_string(),
+            |found| found.to_string_lossy().into_owned(),
+        )
+}
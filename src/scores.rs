@@ -0,0 +1,161 @@
+use crate::{
+    dirs,
+    error::{Error, Result, Source},
+};
+use std::{collections::BTreeMap, fmt, fs, io, path::PathBuf, str::FromStr, time::Duration};
+
+// FNV-1a, stable between runs and builds unlike `DefaultHasher`
+pub fn hash(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Default)]
+pub struct Record {
+    pub name: String,
+    pub score: usize,
+    pub ticks: Option<usize>,
+    pub time: Option<Duration>, // fastest, there's no time limit to have time left of
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Best score: {}", self.score)?;
+        match self.ticks {
+            Some(ticks) => write!(f, "\nFewest ticks: {ticks}")?,
+            None => write!(f, "\nFewest ticks: -")?,
+        }
+        match self.time {
+            Some(time) => write!(f, "\nFastest time: {:.1}s", time.as_secs_f32()),
+            None => write!(f, "\nFastest time: -"),
+        }
+    }
+}
+
+// Best results per level, keyed by the hash of the level contents
+#[derive(Default)]
+pub struct Scores {
+    path: PathBuf,
+    records: BTreeMap<u64, Record>,
+    warning: Option<String>, // why the scores file couldn't be loaded or saved
+}
+
+impl fmt::Display for Scores {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.records.is_empty() {
+            return write!(f, "No scores recorded yet.");
+        }
+
+        for (hash, record) in &self.records {
+            write!(f, "{} ({hash:016x})\n{record}\n\n", record.name)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_optional<T: FromStr>(value: &str) -> Result<Option<T>, T::Err> {
+    match value {
+        "-" => Ok(None),
+        _ => value.parse().map(Some),
+    }
+}
+
+// Line format: <hash> <score> <ticks or -> <time in ms or -> <level name>
+fn parse_record(line: &str) -> Result<(u64, Record), Source> {
+    let fields: Vec<&str> = line.splitn(5, ' ').collect();
+    let [hash, score, ticks, time, name] = fields[..] else {
+        return Err("Expected 5 fields!".into());
+    };
+
+    let record = Record {
+        name: name.to_string(),
+        score: score.parse()?,
+        ticks: parse_optional(ticks)?,
+        time: parse_optional(time)?.map(Duration::from_millis),
+    };
+    Ok((u64::from_str_radix(hash, 16)?, record))
+}
+
+impl Scores {
+    pub fn get(&self, hash: u64) -> Option<&Record> {
+        self.records.get(&hash)
+    }
+
+    // A broken scores file doesn't stop the game, it's left as is and nothing gets recorded
+    pub fn load_or_warn() -> Self {
+        Self::load().unwrap_or_else(|e| Self {
+            warning: Some(format!("Scores aren't recorded: {e}")),
+            ..Default::default()
+        })
+    }
+
+    pub fn get_warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+
+    pub fn load() -> Result<Self> {
+        let mut scores = Self {
+            path: dirs::data_dir().join("scores"),
+            ..Default::default()
+        };
+
+        let path = scores.path.to_string_lossy().into_owned();
+        let contents = match fs::read_to_string(&scores.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(scores),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+
+        for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+            let (hash, record) = parse_record(line)
+                .map_err(|e| Error::parse(&path, format!("Malformed score entry `{line}`!"), e))?;
+            scores.records.insert(hash, record);
+        }
+
+        Ok(scores)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if self.warning.is_some() {
+            return Ok(());
+        }
+
+        let mut contents = String::new();
+        for (hash, record) in &self.records {
+            let ticks = record.ticks.map_or("-".into(), |t| t.to_string());
+            let time = record
+                .time
+                .map_or("-".into(), |t| t.as_millis().to_string());
+            contents += &format!(
+                "{hash:016x} {} {ticks} {time} {}\n",
+                record.score, record.name
+            );
+        }
+
+        let path = self.path.to_string_lossy();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(path.clone(), e))?;
+        }
+        fs::write(&self.path, contents).map_err(|e| Error::io(path, e))
+    }
+
+    // Like loading, a failed save doesn't stop the game, the warning tells about it
+    pub fn save_or_warn(&mut self) {
+        if let Err(e) = self.save() {
+            self.warning = Some(format!("Scores aren't recorded: {e}"));
+        }
+    }
+
+    // `win` is (ticks, time) if the level was completed
+    pub fn submit(&mut self, hash: u64, name: &str, score: usize, win: Option<(usize, Duration)>) {
+        let record = self.records.entry(hash).or_default();
+        record.name = name.to_string();
+        record.score = record.score.max(score);
+
+        if let Some((ticks, time)) = win {
+            record.ticks = Some(record.ticks.map_or(ticks, |t| t.min(ticks)));
+            record.time = Some(record.time.map_or(time, |t| t.min(time)));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    args::Arguments,
+    bdcff,
+    error::{Error, Result},
+    objects::Object,
+    pack::Pack,
+};
+use std::{error, fmt};
+
+#[derive(Debug)]
+pub enum Problem {
+    UnknownChar(char),
+    NoPlayer,
+    ExtraPlayer,
+    NoGems,
+    OpenBorder,
+}
+
+#[derive(Debug)]
+pub struct Issue {
+    pub position: Option<(usize, usize)>, // (line, column) in the file, starting from 1
+    pub problem: Problem,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((line, column)) = self.position {
+            write!(f, "line {line}, column {column}: ")?;
+        }
+        match self.problem {
+            Problem::UnknownChar(chr) => write!(f, "unknown char `{chr}`"),
+            Problem::NoPlayer => write!(f, "no player `p`"),
+            Problem::ExtraPlayer => write!(f, "another player `p`, there can be only one"),
+            Problem::NoGems => write!(f, "no gems `+`, the level can't be won"),
+            Problem::OpenBorder => write!(f, "open border, surround the level with walls `#`"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ValidationError(pub Vec<Issue>);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let issues: Vec<String> = self.0.iter().map(Issue::to_string).collect();
+        write!(f, "{}", issues.join("\n"))
+    }
+}
+
+impl error::Error for ValidationError {}
+
+// Leading lines starting with `;` are metadata like `; title: ...`, the grid follows them
+pub fn split_header(contents: &str) -> (&str, &str) {
+    let len = contents
+        .split_inclusive('\n')
+        .take_while(|line| line.starts_with(';'))
+        .map(str::len)
+        .sum();
+    contents.split_at(len)
+}
+
+// Lines are trimmed the same way `Level::new` does it
+pub fn validate(contents: &str) -> Vec<Issue> {
+    let (header, grid) = split_header(contents);
+    let skipped = header.lines().count();
+    let lines: Vec<(usize, usize, Vec<char>)> = grid
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            let offset = line.chars().take_while(|c| c.is_whitespace()).count();
+            (skipped + idx + 1, offset, line.trim().chars().collect())
+        })
+        .collect();
+    let first = lines.iter().position(|(.., row)| !row.is_empty());
+    let last = lines.iter().rposition(|(.., row)| !row.is_empty());
+    let lines = match (first, last) {
+        (Some(first), Some(last)) => &lines[first..=last],
+        _ => &[],
+    };
+
+    let mut issues = vec![];
+    let mut has_player = false;
+    let mut has_gems = false;
+
+    for (y, (line, offset, row)) in lines.iter().enumerate() {
+        let exists = |x: usize, y: usize| lines.get(y).is_some_and(|(.., row)| x < row.len());
+
+        for (x, &chr) in row.iter().enumerate() {
+            let position = Some((*line, offset + x + 1));
+            let obj = Object::new(chr);
+
+            let problem = match obj {
+                Object::Unknown(_) => Some(Problem::UnknownChar(chr)),
+                Object::Player(_) if has_player => Some(Problem::ExtraPlayer),
+                Object::Wall(_) => None,
+                _ if x == 0 || y == 0 || !exists(x - 1, y) || !exists(x, y - 1) => {
+                    Some(Problem::OpenBorder)
+                }
+                _ if !exists(x + 1, y) || !exists(x, y + 1) => Some(Problem::OpenBorder),
+                _ => None,
+            };
+            has_player |= matches!(obj, Object::Player(_));
+            has_gems |= matches!(obj, Object::Gem(_));
+
+            if let Some(problem) = problem {
+                issues.push(Issue { position, problem });
+            }
+        }
+    }
+
+    if !has_player {
+        issues.push(Issue {
+            position: None,
+            problem: Problem::NoPlayer,
+        });
+    }
+    if !has_gems {
+        issues.push(Issue {
+            position: None,
+            problem: Problem::NoGems,
+        });
+    }
+
+    issues
+}
+
+// `--check`, print the issues of every level
+pub fn check(args: &Arguments) -> Result<()> {
+    let (_, level_paths) = Pack::from_args(args)?;
+    let mut invalid = 0;
+
+    for path in &level_paths {
+        let issues = validate(&bdcff::read_level(path)?);
+        if issues.is_empty() {
+            println!("{path}: ok");
+        } else {
+            invalid += 1;
+            println!("{path}:\n{}", ValidationError(issues));
+        }
+    }
+
+    match invalid {
+        0 => Ok(()),
+        _ => Err(Error::Check {
+            invalid,
+            total: level_paths.len(),
+        }),
+    }
+}
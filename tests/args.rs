@@ -0,0 +1,91 @@
+use boulder_dash::{Arguments, Command, InteractionMode, Layout};
+use std::{env, fs, time::Duration};
+
+// Without `--config` the config directory is read, keep the user's one out of it
+fn parse(args: &[&str]) -> boulder_dash::Result<Command> {
+    env::set_var(
+        "XDG_CONFIG_HOME",
+        format!("{}/no_config", env!("CARGO_TARGET_TMPDIR")),
+    );
+    Arguments::parse(args.iter().map(ToString::to_string))
+}
+
+fn parse_run(args: &[&str]) -> Arguments {
+    match parse(args).unwrap() {
+        Command::Run(args) => args,
+        command => panic!("Expected `Command::Run`, got {command:?}"),
+    }
+}
+
+fn write_config(name: &str, contents: &str) -> String {
+    let path = format!("{}/{name}", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn config_sets_defaults() {
+    let path = write_config(
+        "config_sets_defaults",
+        "# Defaults\nmode = cli\ndelay = 200\nlayout = qwerty\nlevels = assets/levels\n",
+    );
+    let args = parse_run(&["--config", &path, "-l", "test"]);
+    assert_eq!(args.interaction_mode, InteractionMode::Cli);
+    assert_eq!(args.delay, Duration::from_millis(200));
+    assert_eq!(args.layout, Layout::Qwerty);
+    assert_eq!(args.level_dirs, ["assets/levels"]);
+}
+
+#[test]
+fn flags_override_config() {
+    let path = write_config("flags_override_config", "mode = cli\ndelay = 200\n");
+    let args = parse_run(&["-d", "300", "--config", &path, "-m", "gui", "-l", "x"]);
+    assert_eq!(args.interaction_mode, InteractionMode::Gui);
+    assert_eq!(args.delay, Duration::from_millis(300));
+}
+
+#[test]
+fn bad_config_is_rejected() {
+    let path = write_config("bad_config_is_rejected", "speed = 3\n");
+    assert!(parse(&["--config", &path, "-l", "x"]).is_err());
+    assert!(parse(&["--config", "/nonexistent/config", "-l", "x"]).is_err());
+}
+
+#[test]
+fn long_options_take_inline_values() {
+    let args = parse_run(&["--delay=200", "--mode=cli", "--level=a"]);
+    assert_eq!(args.delay, Duration::from_millis(200));
+    assert_eq!(args.interaction_mode, InteractionMode::Cli);
+    assert_eq!(args.level_paths, ["a"]);
+    assert!(parse(&["--pause=yes", "a"]).is_err());
+}
+
+#[test]
+fn new_level_size() {
+    assert_eq!(parse_run(&["--new-size", "30x12", "a"]).new_size, (30, 12));
+    assert!(parse(&["--new-size=30", "a"]).is_err());
+    assert!(parse(&["--new-size=0x5", "a"]).is_err());
+}
+
+#[test]
+fn positional_level_paths() {
+    let args = parse_run(&["a", "-l", "b", "c", "--", "-d"]);
+    assert_eq!(args.level_paths, ["a", "b", "c", "-d"]);
+}
+
+#[test]
+fn grouped_short_flags() {
+    let args = parse_run(&["-pl", "a", "-d200"]);
+    assert!(args.pause);
+    assert_eq!(args.level_paths, ["a"]);
+    assert_eq!(args.delay, Duration::from_millis(200));
+    assert_eq!(parse_run(&["-lp"]).level_paths, ["p"]);
+    assert!(parse(&["-pl"]).is_err());
+}
+
+#[test]
+fn help_and_version() {
+    assert_eq!(parse(&["-ph"]).unwrap(), Command::Help);
+    assert_eq!(parse(&["a", "--help", "--bogus"]).unwrap(), Command::Help);
+    assert_eq!(parse(&["--version"]).unwrap(), Command::Version);
+}
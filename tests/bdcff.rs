@@ -0,0 +1,61 @@
+use boulder_dash::{read_level, Error};
+
+// BDCFF caves in `tests/fixtures/bdcff`, read through `file#n` like the `-l` paths
+const FIXTURES: &str = "tests/fixtures/bdcff";
+
+fn format_error(path: &str) -> String {
+    match read_level(&format!("{FIXTURES}/{path}")) {
+        Err(Error::Format { message, .. }) => message,
+        result => panic!("Expected a format error for `{path}`, got {result:?}"),
+    }
+}
+
+#[test]
+fn header_is_detected() {
+    // Case and leading whitespace don't matter, the first cave is the default
+    let first = read_level(&format!("{FIXTURES}/caves.bd")).unwrap();
+    assert_eq!(
+        first,
+        read_level(&format!("{FIXTURES}/caves.bd#1")).unwrap()
+    );
+    assert_eq!(
+        read_level(&format!("{FIXTURES}/crlf.bd")).unwrap(),
+        "##\n#p"
+    );
+
+    // Anything else is one of our own levels
+    let path = format!("{FIXTURES}/not_bdcff.level");
+    assert_eq!(
+        read_level(&path).unwrap(),
+        "# [BDCFF] only counts at the start\n#+p#\n"
+    );
+}
+
+#[test]
+fn elements_are_mapped() {
+    assert_eq!(
+        read_level(&format!("{FIXTURES}/caves.bd#1")).unwrap(),
+        "########\n#####*O#\n#+p    #\n########"
+    );
+    assert_eq!(
+        read_level(&format!("{FIXTURES}/caves.bd#2")).unwrap(),
+        "###\n#p#\n###"
+    );
+}
+
+#[test]
+fn malformed_caves_are_rejected() {
+    assert_eq!(
+        format_error("unterminated.bd"),
+        "Unterminated [map] section!"
+    );
+    assert_eq!(
+        format_error("map_outside_cave.bd"),
+        "[map] outside of a [cave]!"
+    );
+    assert_eq!(
+        format_error("no_caves.bd"),
+        "No caves with a [map] section found!"
+    );
+    assert_eq!(format_error("caves.bd#3"), "There are only 2 caves!");
+}
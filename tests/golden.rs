@@ -0,0 +1,73 @@
+use boulder_dash::{Arguments, Game, Script};
+use std::{env, fs, time::Duration};
+
+// Every fixture in `tests/fixtures` is a `<name>.level` played with the inputs
+// in `<name>.inputs`, the frames have to match `tests/golden/<name>.frames`.
+// Run with `UPDATE_GOLDEN=1` to rewrite the golden files after an intended change.
+const FIXTURES: &str = "tests/fixtures";
+const GOLDEN: &str = "tests/golden";
+const SEPARATOR: &str = "\n~~~~~~\n";
+
+fn render(name: &str) -> String {
+    // Scores are recorded on a loss, keep them out of the user's data directory.
+    // The hints come from the keys, without the user's keys file.
+    env::set_var("XDG_DATA_HOME", env!("CARGO_TARGET_TMPDIR"));
+    env::set_var(
+        "XDG_CONFIG_HOME",
+        format!("{}/no_config", env!("CARGO_TARGET_TMPDIR")),
+    );
+
+    let args = Arguments {
+        delay: Duration::ZERO,
+        level_paths: vec![format!("{FIXTURES}/{name}.level")],
+        ..Default::default()
+    };
+    let mut script = Script::from_file(&format!("{FIXTURES}/{name}.inputs")).unwrap();
+    Game::new(&args).unwrap().run(&mut script).unwrap();
+
+    script.get_frames().join(SEPARATOR) + "\n"
+}
+
+fn check(name: &str) {
+    let actual = render(name);
+    let path = format!("{GOLDEN}/{name}.frames");
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("No `{path}`, run with UPDATE_GOLDEN=1 to create it"));
+    let actual: Vec<&str> = actual.split(SEPARATOR).collect();
+    let expected: Vec<&str> = expected.split(SEPARATOR).collect();
+
+    for (idx, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+        assert_eq!(actual, expected, "`{name}`: frame {idx} differs");
+    }
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "`{name}`: frame count differs"
+    );
+}
+
+#[test]
+fn rock_falls() {
+    check("rock_falls");
+}
+
+#[test]
+fn rock_rolls() {
+    check("rock_rolls");
+}
+
+#[test]
+fn rock_pushed() {
+    check("rock_pushed");
+}
+
+#[test]
+fn rock_crushes_player() {
+    check("rock_crushes_player");
+}
@@ -0,0 +1,27 @@
+use boulder_dash::{Error, Gui, Keymap};
+use std::fs;
+
+#[test]
+fn theme_without_a_sprite_is_rejected() {
+    // Every sprite but the one for unknown chars
+    let theme = format!("{}/theme_without_unknown", env!("CARGO_TARGET_TMPDIR"));
+    fs::create_dir_all(&theme).unwrap();
+    for entry in fs::read_dir("assets/sprites").unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_name() != "unknown" {
+            fs::copy(
+                entry.path(),
+                format!("{theme}/{}", entry.file_name().to_string_lossy()),
+            )
+            .unwrap();
+        }
+    }
+    let _ = fs::remove_file(format!("{theme}/unknown"));
+
+    // Fails before a window opens
+    match Gui::new(32, Some(&theme), Keymap::default()) {
+        Err(Error::Asset { path, .. }) => assert_eq!(path, format!("{theme}/unknown")),
+        Err(err) => panic!("Expected an asset error, got {err}"),
+        Ok(_) => panic!("The theme has no `unknown` sprite"),
+    }
+}
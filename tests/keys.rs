@@ -0,0 +1,48 @@
+use boulder_dash::{Action, Direction, Keymap, Layout, ProgramMode};
+
+#[test]
+fn presets() {
+    let qwerty = Keymap::new(Layout::Qwerty, &ProgramMode::Game);
+    assert_eq!(qwerty.get("s"), Action::Move(Direction::Down));
+    assert_eq!(qwerty.get("r"), Action::Restart);
+    assert_eq!(qwerty.get("p"), Action::Unknown);
+
+    let colemak = Keymap::new(Layout::Colemak, &ProgramMode::Game);
+    assert_eq!(colemak.get("r"), Action::Move(Direction::Down));
+    assert_eq!(colemak.get("p"), Action::Restart);
+    assert_eq!(colemak.get("escape"), Action::Back);
+
+    let editor = Keymap::new(Layout::Colemak, &ProgramMode::Editor);
+    assert_eq!(editor.get("escape"), Action::Save);
+    assert_eq!(editor.get("space"), Action::UseTool);
+}
+
+#[test]
+fn sections_apply_per_program_mode() {
+    let contents = "\
+# Both modes
+k up
+[game]
+x restart
+j none
+[editor]
+x use_tool
+";
+    let mut game = Keymap::new(Layout::Qwerty, &ProgramMode::Game);
+    game.apply(contents, &ProgramMode::Game).unwrap();
+    assert_eq!(game.get("k"), Action::Move(Direction::Up));
+    assert_eq!(game.get("x"), Action::Restart);
+    assert_eq!(game.get("j"), Action::Unknown);
+
+    let mut editor = Keymap::new(Layout::Qwerty, &ProgramMode::Editor);
+    editor.apply(contents, &ProgramMode::Editor).unwrap();
+    assert_eq!(editor.get("k"), Action::Move(Direction::Up));
+    assert_eq!(editor.get("x"), Action::UseTool);
+}
+
+#[test]
+fn bad_lines_are_rejected() {
+    let mut keymap = Keymap::default();
+    assert!(keymap.apply("x", &ProgramMode::Game).is_err());
+    assert!(keymap.apply("x jump", &ProgramMode::Game).is_err());
+}
@@ -0,0 +1,88 @@
+use boulder_dash::{Action, Arguments, Direction, Error, Game, Pack, Script};
+use std::{env, fs, time::Duration};
+
+// A manifest and its levels in `tests/fixtures/pack`, plus broken manifests
+const FIXTURES: &str = "tests/fixtures/pack";
+
+fn format_error(path: &str) -> String {
+    match Pack::load(&format!("{FIXTURES}/{path}")) {
+        Err(Error::Format { message, .. }) => message,
+        Err(err) => panic!("Expected a format error for `{path}`, got {err}"),
+        Ok(_) => panic!("Expected a format error for `{path}`"),
+    }
+}
+
+#[test]
+fn pack_paths_are_relative_to_the_manifest() {
+    let pack = Pack::load(&format!("{FIXTURES}/pack")).unwrap();
+    assert_eq!(pack.name, "Test pack");
+    assert_eq!(pack.author, "Tests");
+    assert_eq!(pack.description, "Levels next to the manifest");
+    assert_eq!(
+        pack.level_paths,
+        [
+            format!("{FIXTURES}/first.level"),
+            format!("{FIXTURES}/second.level")
+        ]
+    );
+
+    // A directory stands for the `pack` manifest in it
+    let dir = Pack::load(FIXTURES).unwrap();
+    assert_eq!(dir.level_paths, pack.level_paths);
+}
+
+#[test]
+fn broken_packs_are_rejected() {
+    assert_eq!(format_error("empty"), "The pack has no levels!");
+    assert_eq!(
+        format_error("unknown_key"),
+        "Unrecognized line 2: `levels first.level`!"
+    );
+}
+
+#[test]
+fn levels_unlock_in_order() {
+    // Completion comes from the scores, start without any
+    let data = format!("{}/pack_scores", env!("CARGO_TARGET_TMPDIR"));
+    let _ = fs::remove_dir_all(&data);
+    env::set_var("XDG_DATA_HOME", &data);
+    env::set_var(
+        "XDG_CONFIG_HOME",
+        format!("{}/no_config", env!("CARGO_TARGET_TMPDIR")),
+    );
+
+    let args = Arguments {
+        delay: Duration::ZERO,
+        pack_path: Some(FIXTURES.into()),
+        ..Default::default()
+    };
+    let inputs = [
+        Action::Move(Direction::Down),
+        Action::TogglePause,
+        Action::Move(Direction::Up),
+        Action::TogglePause,
+        Action::Move(Direction::Right),
+        Action::Unknown,
+        Action::TogglePause,
+    ];
+    let mut script = Script::new(inputs);
+    Game::new(&args).unwrap().run(&mut script).unwrap();
+    let frames = script.get_frames();
+
+    let first = format!("1. {FIXTURES}/first.level");
+    let second = format!("2. {FIXTURES}/second.level");
+    assert!(frames[0].contains(&format!(
+        "Test pack: select a level\n> {first}\n  {second} (locked)"
+    )));
+    // The locked level can be selected but not played
+    assert!(frames[1].contains(&format!("> {second} (locked)")));
+    assert_eq!(frames[2], frames[1]);
+    assert!(frames[4].starts_with("####\n#p+#\n####\n"));
+    // Winning the first level unlocks the second and preselects it
+    let menu = frames
+        .iter()
+        .rposition(|frame| frame.contains("select a level"))
+        .unwrap();
+    assert!(frames[menu].contains(&format!("  {first} (completed)\n> {second}\n")));
+    assert!(frames[menu + 1].starts_with("#####\n#p +#\n#####\n"));
+}
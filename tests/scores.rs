@@ -0,0 +1,54 @@
+use boulder_dash::{Action, Arguments, Direction, Game, Script};
+use std::{env, fs, sync::Mutex, time::Duration};
+
+// Each test has a data directory of its own, set one at a time
+static DATA_HOME: Mutex<()> = Mutex::new(());
+
+// Losing the level records a score
+fn lose(data: &str) -> Vec<String> {
+    let _lock = DATA_HOME.lock().unwrap();
+    env::set_var("XDG_DATA_HOME", data);
+    env::set_var(
+        "XDG_CONFIG_HOME",
+        format!("{}/no_config", env!("CARGO_TARGET_TMPDIR")),
+    );
+
+    let args = Arguments {
+        delay: Duration::ZERO,
+        level_paths: vec!["tests/fixtures/rock_crushes_player.level".into()],
+        ..Default::default()
+    };
+    let mut script = Script::new([Action::Move(Direction::Up), Action::Unknown]);
+    Game::new(&args).unwrap().run(&mut script).unwrap();
+    script.get_frames().to_vec()
+}
+
+#[test]
+fn broken_scores_file_is_kept() {
+    let data = format!("{}/broken_scores", env!("CARGO_TARGET_TMPDIR"));
+    let path = format!("{data}/boulder_dash/scores");
+    fs::create_dir_all(format!("{data}/boulder_dash")).unwrap();
+    fs::write(&path, "not a score entry\n").unwrap();
+
+    let frames = lose(&data);
+    assert!(frames[0].contains("Scores aren't recorded"));
+    assert!(frames.last().unwrap().contains("You have lost!"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "not a score entry\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_scores_save_is_shown() {
+    let data = format!("{}/unwritable_scores", env!("CARGO_TARGET_TMPDIR"));
+    let path = format!("{data}/boulder_dash/scores");
+    fs::create_dir_all(format!("{data}/boulder_dash")).unwrap();
+    // Reads as missing, can't be written through
+    let _ = fs::remove_file(&path);
+    std::os::unix::fs::symlink(format!("{data}/missing/scores"), &path).unwrap();
+
+    let frames = lose(&data);
+    assert!(!frames[0].contains("Scores aren't recorded"));
+    let last = frames.last().unwrap();
+    assert!(last.contains("You have lost!"));
+    assert!(last.contains("Scores aren't recorded: Can't access"));
+}
@@ -0,0 +1,812 @@
+use boulder_dash::{
+    Action, Arguments, Direction, Drawable, Editor, Error, Game, Interaction, Keymap, Layout,
+    ProgramMode, Result, Script,
+};
+use std::{collections::VecDeque, env, error, fs, mem, sync::Once, time::Duration};
+
+// Scores are recorded on a win or a loss, keep them out of the user's data directory.
+// The user's keys file is swapped for one that moves copy from C to K
+// and the level select from Tab to L.
+fn setup() {
+    static KEYS: Once = Once::new();
+    let config = format!("{}/script_config", env!("CARGO_TARGET_TMPDIR"));
+    KEYS.call_once(|| {
+        fs::create_dir_all(format!("{config}/boulder_dash")).unwrap();
+        fs::write(
+            format!("{config}/boulder_dash/keys"),
+            "[editor]\nc none\nk copy\n[game]\ntab none\nl level_select\n",
+        )
+        .unwrap();
+    });
+    env::set_var("XDG_DATA_HOME", env!("CARGO_TARGET_TMPDIR"));
+    env::set_var("XDG_CONFIG_HOME", config);
+}
+
+// The editor writes the level, so it gets a copy
+fn copy_level(name: &str) -> String {
+    let path = format!("{}/{name}", env!("CARGO_TARGET_TMPDIR"));
+    fs::copy("assets/levels/test", &path).unwrap();
+    path
+}
+
+fn game_args(delay: Duration) -> Arguments {
+    Arguments {
+        delay,
+        level_paths: vec!["assets/levels/test".into()],
+        ..Default::default()
+    }
+}
+
+fn run_game(args: &Arguments, inputs: impl IntoIterator<Item = Action>) -> Vec<String> {
+    setup();
+    let mut script = Script::new(inputs);
+    Game::new(args).unwrap().run(&mut script).unwrap();
+    script.get_frames().to_vec()
+}
+
+fn editor_args(path: &str) -> Arguments {
+    Arguments {
+        level_paths: vec![path.into()],
+        program_mode: ProgramMode::Editor,
+        ..Default::default()
+    }
+}
+
+// `text` is typed into the prompts
+fn run_editor(
+    args: &Arguments,
+    inputs: impl IntoIterator<Item = Action>,
+    text: &str,
+) -> Vec<String> {
+    setup();
+    let mut script = Script::new(inputs).with_text(text);
+    Editor::new(args).unwrap().run(&mut script).unwrap();
+    script.get_frames().to_vec()
+}
+
+#[test]
+fn game_quits() {
+    let frames = run_game(&game_args(Duration::ZERO), [Action::Quit]);
+    assert_eq!(frames.len(), 1);
+    assert!(frames[0].starts_with("######\n#+ Op#\n"));
+}
+
+#[test]
+fn game_quits_when_script_runs_out() {
+    let frames = run_game(&game_args(Duration::ZERO), []);
+    assert_eq!(frames.len(), 1);
+}
+
+#[test]
+fn game_reloads() {
+    let frames = run_game(
+        &game_args(Duration::ZERO),
+        [Action::Move(Direction::Left), Action::Restart, Action::Quit],
+    );
+    assert_eq!(frames.len(), 3);
+    assert!(frames[1].starts_with("######\n#+Op #\n"));
+    assert_eq!(frames[2], frames[0]);
+}
+
+#[test]
+fn game_hints_follow_the_keymap() {
+    let args = Arguments {
+        delay: Duration::ZERO,
+        layout: Layout::Qwerty,
+        level_paths: vec!["assets/levels/test".into(), "assets/levels/test".into()],
+        ..Default::default()
+    };
+    let frames = run_game(&args, [Action::TogglePause, Action::Quit]);
+    assert!(frames[0].ends_with("Space - play, Esc - back, Q - quit"));
+    assert!(frames[1].ends_with("L - level select"));
+
+    // Restart is on R with QWERTY and on P with Colemak
+    for (layout, hint) in [
+        (Layout::Qwerty, "R - reload"),
+        (Layout::Colemak, "P - reload"),
+    ] {
+        let args = Arguments {
+            delay: Duration::ZERO,
+            layout,
+            level_paths: vec!["tests/fixtures/rock_crushes_player.level".into()],
+            ..Default::default()
+        };
+        let frames = run_game(&args, [Action::Move(Direction::Up), Action::Unknown]);
+        assert!(frames
+            .last()
+            .unwrap()
+            .ends_with(&format!("You have lost!\n{hint}")));
+    }
+}
+
+#[test]
+fn game_pauses() {
+    let frames = run_game(
+        &game_args(Duration::from_secs(1)),
+        [Action::TogglePause, Action::Back],
+    );
+    assert!(frames[0].ends_with("Paused: no"));
+    assert!(frames[1].ends_with("Paused: yes"));
+    assert!(frames[2].ends_with("Paused: no"));
+}
+
+#[test]
+fn game_paused_doesnt_tick() {
+    let inputs = [
+        Action::TogglePause,
+        Action::Unknown,
+        Action::Unknown,
+        Action::Move(Direction::Left),
+    ];
+    let frames = run_game(&game_args(Duration::ZERO), inputs);
+    // A direction still moves the player while paused
+    assert_eq!(frames.len(), 2);
+    assert!(frames[1].starts_with("######\n#+Op #\n"));
+}
+
+#[test]
+fn game_changes_delay() {
+    let inputs = [Action::SpeedUp, Action::SpeedUp, Action::SlowDown];
+    let frames = run_game(&game_args(Duration::from_secs(1)), inputs);
+    assert!(frames[1].contains("Delay: 950ms"));
+    assert!(frames[2].contains("Delay: 900ms"));
+    assert!(frames[3].contains("Delay: 950ms"));
+}
+
+#[test]
+fn editor_cycles_palette() {
+    let path = copy_level("editor_cycles_palette");
+    let inputs = [
+        Action::PrevPalette,
+        Action::NextPalette,
+        Action::NextPalette,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[0].ends_with("[void] wall rock dirt gem player"));
+    assert!(frames[1].ends_with("void wall rock dirt gem [player]"));
+    assert!(frames[2].ends_with("[void] wall rock dirt gem player"));
+    assert!(frames[3].ends_with("void [wall] rock dirt gem player"));
+}
+
+#[test]
+fn editor_reloads() {
+    let path = copy_level("editor_reloads");
+    let inputs = [
+        Action::NextPalette,
+        Action::NextPalette,
+        Action::UseTool,
+        Action::Restart,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[3].starts_with("O#####\n"));
+    assert!(frames[3].contains("Pen down"));
+    assert_eq!(frames[4], frames[2]);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        fs::read_to_string("assets/levels/test").unwrap().trim()
+    );
+}
+
+#[test]
+fn editor_saves() {
+    let path = copy_level("editor_saves");
+    let inputs = [
+        Action::Move(Direction::Down),
+        Action::Move(Direction::Right),
+        Action::Move(Direction::Right),
+        Action::NextPalette,
+        Action::UseTool,
+        Action::Save,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[5].contains("Cursor pos: (2, 1)"));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "######\n#+#Op#\n##**##\n######"
+    );
+    assert_eq!(
+        fs::read_to_string(format!("{path}.bak")).unwrap(),
+        fs::read_to_string("assets/levels/test").unwrap()
+    );
+    assert!(fs::metadata(format!("{path}.tmp")).is_err());
+}
+
+#[test]
+fn editor_undoes_strokes() {
+    let path = copy_level("editor_undoes_strokes");
+    let inputs = [
+        Action::Move(Direction::Down),
+        Action::Move(Direction::Right),
+        Action::NextPalette,
+        Action::UseTool,
+        Action::Move(Direction::Right),
+        Action::UseTool,
+        Action::Undo,
+        Action::Redo,
+        Action::Undo,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    let grid = |idx: usize| frames[idx].split("\n\n").next().unwrap();
+    assert!(grid(6).starts_with("######\n###Op#\n"));
+    // The whole stroke is one step
+    assert_eq!(grid(7), grid(3));
+    assert_eq!(grid(8), grid(6));
+    assert_eq!(grid(9), grid(3));
+}
+
+#[test]
+fn editor_undoes_reload() {
+    let path = copy_level("editor_undoes_reload");
+    // Paint void over the corner, reload and get it back
+    let inputs = [
+        Action::UseTool,
+        Action::UseTool,
+        Action::Restart,
+        Action::Undo,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[2].starts_with(" #####\n"));
+    assert!(frames[3].starts_with("######\n"));
+    assert!(frames[4].starts_with(" #####\n"));
+}
+
+#[test]
+fn editor_skips_strokes_that_change_nothing() {
+    let path = copy_level("editor_skips_strokes");
+    let inputs = [
+        Action::NextPalette,
+        Action::NextPalette,
+        Action::UseTool,
+        Action::UseTool,
+        // Rock over the rock
+        Action::UseTool,
+        Action::UseTool,
+        Action::Undo,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[6].starts_with("O#####\n"));
+    assert!(frames[7].starts_with("######\n"));
+}
+
+#[test]
+fn editor_copies_and_pastes() {
+    let path = copy_level("editor_copies_and_pastes");
+    let inputs = [
+        Action::Move(Direction::Down),
+        Action::Select,
+        Action::Move(Direction::Right),
+        Action::Copy,
+        Action::Mirror,
+        Action::Paste,
+        Action::Undo,
+        Action::Rotate,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    // The hints follow the keys file, copy is moved from C to K
+    assert!(frames[3].contains("Selecting 2x1: K - copy, X - cut, V - cancel"));
+    // The preview follows the cursor
+    assert!(frames[4].starts_with("######\n##+Op#\n"));
+    assert!(frames[4].contains("Pasting 2x1"));
+    assert!(frames[5].starts_with("######\n#+#Op#\n"));
+    assert!(!frames[6].contains("Pasting"));
+    assert!(frames[7].starts_with("######\n#+ Op#\n"));
+    assert!(frames[8].contains("Pasting 1x2"));
+}
+
+#[test]
+fn editor_fills_and_draws_lines() {
+    let path = copy_level("editor_fills_and_draws_lines");
+    let mut inputs = vec![Action::Move(Direction::Down); 2];
+    inputs.extend([Action::Move(Direction::Right); 2]);
+    inputs.extend([Action::NextPalette; 4]); // gem
+    inputs.extend([
+        Action::NextTool,
+        Action::UseTool,
+        Action::NextTool,
+        Action::UseTool,
+        Action::Move(Direction::Up),
+        Action::Move(Direction::Right),
+        Action::UseTool,
+        Action::Save,
+    ]);
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[9].contains("\n\nTool: fill\n"));
+    assert!(frames[10].starts_with("######\n#+ Op#\n##++##\n"));
+    assert!(frames[12].contains("Tool: line from (2, 2)"));
+    // Previewed before it's drawn
+    assert!(frames[14].starts_with("######\n#+ +p#\n##++##\n"));
+    assert!(frames[15].contains("Tool: line\n"));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "######\n#+ +p#\n##++##\n######"
+    );
+}
+
+#[test]
+fn editor_paints_with_mouse() {
+    let path = copy_level("editor_paints_with_mouse");
+    let inputs = [
+        Action::Pick((1, 1)),
+        Action::Click((2, 1)),
+        Action::Drag((4, 2)),
+        Action::Undo,
+        Action::SelectObject(1),
+        Action::SelectObject(99),
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames[1].contains("[gem]"));
+    assert!(frames[2].starts_with("######\n#++Op#\n##**##\n"));
+    // Dragging draws a line from the last painted cell
+    assert!(frames[3].starts_with("######\n#++Op#\n##*++#\n"));
+    assert!(frames[3].contains("Cursor pos: (4, 2)"));
+    assert_eq!(frames[4], frames[1].replace("(0, 0)", "(4, 2)"));
+    // Palette sidebar clicks, out of range ones are ignored
+    assert!(frames[5].contains("[wall]"));
+    assert_eq!(frames.len(), 6);
+}
+
+#[test]
+fn editor_ends_strokes_on_release() {
+    let path = copy_level("editor_ends_strokes_on_release");
+    let inputs = [
+        Action::Pick((1, 1)),
+        Action::Click((2, 1)),
+        Action::Release,
+        Action::Drag((4, 2)),
+        Action::Click((99, 99)),
+        Action::Drag((2, 2)),
+        Action::Undo,
+        Action::Undo,
+        Action::Undo,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    // A drag after a release or a click outside the level starts a new stroke, no line
+    assert!(frames[4].starts_with("######\n#++Op#\n##**+#\n"));
+    assert!(frames[6].starts_with("######\n#++Op#\n##+*+#\n"));
+    // Each stroke is its own undo step
+    assert!(frames[7].starts_with("######\n#++Op#\n##**+#\n"));
+    assert!(frames[8].starts_with("######\n#++Op#\n##**##\n"));
+    assert!(frames[9].starts_with("######\n#+ Op#\n##**##\n"));
+}
+
+#[test]
+fn editor_playtests_unsaved_level() {
+    setup();
+    let path = copy_level("editor_playtests_unsaved_level");
+    let args = Arguments {
+        delay: Duration::ZERO,
+        ..editor_args(&path)
+    };
+    let inputs = [
+        Action::Click((2, 2)),
+        Action::Playtest,
+        Action::Move(Direction::Left),
+        Action::Quit,
+        Action::Undo,
+    ];
+    let frames = run_editor(&args, inputs, "");
+
+    assert!(frames[2].starts_with("######\n#+ Op#\n## *##\n"));
+    assert!(frames[2].contains("Score: 0/1"));
+    assert!(frames[3].starts_with("######\n#+ p #\n##O*##\n"));
+    // Back in the editor with the edit and its history
+    assert!(frames[4].starts_with("######\n#+ Op#\n## *##\n"));
+    assert!(frames[4].contains("Cursor pos: (2, 2)"));
+    assert!(frames[5].starts_with("######\n#+ Op#\n##**##\n"));
+}
+
+// Presses keys by name like the TUI does, so the actions come from its keymap
+struct Keys {
+    keymap: Keymap,
+    keys: VecDeque<&'static str>,
+    frames: Vec<String>,
+}
+
+impl Interaction for Keys {
+    fn get_action(&mut self) -> Action {
+        self.keys
+            .pop_front()
+            .map_or(Action::Quit, |key| self.keymap.get(key))
+    }
+
+    fn draw(&mut self, drawable: &mut dyn Drawable) -> Result<()> {
+        drawable.get_damaged();
+        self.frames.push(drawable.get_frame());
+        Ok(())
+    }
+
+    fn swap_keymap(&mut self, keymap: Keymap) -> Keymap {
+        mem::replace(&mut self.keymap, keymap)
+    }
+}
+
+#[test]
+fn editor_playtests_with_game_keys() {
+    setup();
+    let path = copy_level("editor_playtests_with_game_keys");
+    let args = Arguments {
+        delay: Duration::from_secs(3600),
+        level_paths: vec![path],
+        program_mode: ProgramMode::Editor,
+        ..Default::default()
+    };
+    let mut keys = Keys {
+        keymap: Keymap::new(Layout::default(), &ProgramMode::Editor),
+        keys: VecDeque::from(["g", "space", "q", "space"]),
+        frames: vec![],
+    };
+    Editor::new(&args).unwrap().run(&mut keys).unwrap();
+    let frames = keys.frames;
+
+    // Space pauses the playtest instead of using the editor's pen
+    assert!(frames[2].contains("Paused: yes"));
+    // and uses the pen again once it is quit
+    assert!(frames[3].contains("Pen up"));
+    assert!(frames[4].contains("Pen down"));
+}
+
+#[test]
+fn editor_reshapes_level() {
+    let path = copy_level("editor_reshapes_level");
+    let inputs = [
+        Action::DeleteRow,
+        Action::InsertColumn,
+        Action::WrapBorder,
+        Action::Undo,
+        Action::Move(Direction::Down),
+        Action::Move(Direction::Right),
+        Action::ResizeToCursor,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    let grid = |idx: usize| frames[idx].split("\n\n").next().unwrap();
+    assert_eq!(grid(1), "#+ Op#\n##**##\n######");
+    assert_eq!(grid(2), " #+ Op#\n ##**##\n ######");
+    assert_eq!(
+        grid(3),
+        "#########\n# #+ Op##\n# ##**###\n# #######\n#########"
+    );
+    assert!(frames[3].contains("Cursor pos: (1, 1)"));
+    assert_eq!(grid(4), grid(2));
+    // The cursor moved with the border, (2, 2) is the new corner
+    assert_eq!(grid(7), " #+\n ##\n ##");
+}
+
+#[test]
+fn editor_trims_only_when_enabled() {
+    // Void painted past the end of the first row, then the pen is lifted
+    let mut inputs = vec![Action::Move(Direction::Right); 6];
+    inputs.extend([
+        Action::UseTool,
+        Action::UseTool,
+        Action::Move(Direction::Left),
+    ]);
+
+    for trim in [false, true] {
+        let path = copy_level(&format!("editor_trims_{trim}"));
+        let args = Arguments {
+            trim,
+            ..editor_args(&path)
+        };
+        let frames = run_editor(&args, inputs.clone(), "");
+
+        let first_row = if trim { "######\n" } else { "###### \n" };
+        assert!(frames[8].starts_with("###### \n"));
+        assert!(frames[9].starts_with(first_row));
+    }
+}
+
+#[test]
+fn editor_confirms_quit_with_unsaved_changes() {
+    let path = copy_level("editor_confirms_quit");
+    let inputs = [
+        Action::NextPalette,
+        Action::NextPalette,
+        Action::UseTool,
+        Action::Quit,
+        Action::Undo,
+        Action::Quit,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(!frames[0].contains("(modified)"));
+    assert!(frames[3].contains("editor_confirms_quit (modified)"));
+    assert!(frames[4].contains("Unsaved changes: Q - quit anyway"));
+    // Undone back to the file, nothing to confirm
+    assert!(!frames[5].contains("(modified)"));
+    assert_eq!(frames.len(), 6);
+
+    // Quit twice without saving
+    let inputs = [Action::NextPalette, Action::NextPalette, Action::UseTool];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert!(frames.last().unwrap().contains("Unsaved changes"));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        fs::read_to_string("assets/levels/test").unwrap()
+    );
+}
+
+#[test]
+fn editor_keeps_history_with_one_level() {
+    let path = copy_level("editor_keeps_history_with_one_level");
+    let frames = run_editor(
+        &editor_args(&path),
+        [Action::UseTool, Action::NextLevel, Action::Undo],
+        "",
+    );
+    assert!(frames[1].starts_with(" #####\n"));
+    // Nothing to switch to, so nothing to confirm or to reload
+    assert_eq!(frames[2], frames[1]);
+    assert!(frames[3].starts_with("######\n"));
+}
+
+#[test]
+fn editor_creates_and_saves_as() {
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let path = format!("{dir}/editor_creates_new");
+    let other = format!("{dir}/editor_creates_other");
+    let _ = fs::remove_file(&path);
+    let args = Arguments {
+        new_size: (4, 3),
+        ..editor_args(&path)
+    };
+    let frames = run_editor(&args, [Action::SaveAs], &format!("x\u{8}{other}\n"));
+
+    assert!(frames[0].starts_with("####\n#  #\n####\n"));
+    assert!(frames[0].contains("editor_creates_new (modified)"));
+    assert!(frames[1].ends_with("Save as: _ (Enter - save, Esc - cancel)"));
+    assert!(frames[2].contains("Save as: x_"));
+    assert_eq!(frames[3], frames[1]);
+    assert!(!frames.last().unwrap().contains("(modified)"));
+    assert_eq!(fs::read_to_string(&other).unwrap(), "####\n#  #\n####");
+    assert!(fs::metadata(&path).is_err());
+}
+
+#[test]
+fn editor_reports_failed_save_as() {
+    let path = copy_level("editor_reports_failed_save_as");
+    let other = format!("{}/missing_dir/level", env!("CARGO_TARGET_TMPDIR"));
+    let inputs = [Action::SaveAs, Action::Save];
+    let frames = run_editor(&editor_args(&path), inputs, &format!("{other}\n"));
+
+    // The level keeps its path and the next save clears the warning
+    let failed = &frames[frames.len() - 2];
+    assert!(failed.contains("editor_reports_failed_save_as\n"));
+    assert!(failed.contains(&format!("Save failed: Can't access `{other}.tmp`")));
+    assert!(!frames.last().unwrap().contains("Save failed"));
+    assert!(fs::metadata(&other).is_err());
+}
+
+#[test]
+fn editor_keeps_editing_after_a_failed_save() {
+    let path = copy_level("editor_keeps_editing_after_a_failed_save");
+    let tmp = format!("{path}.tmp");
+    fs::create_dir_all(&tmp).unwrap();
+    let inputs = [Action::UseTool, Action::Save, Action::Undo, Action::Redo];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    fs::remove_dir(&tmp).unwrap();
+
+    assert!(frames[2].contains(&format!("Save failed: Can't access `{tmp}`")));
+    assert!(frames[2].contains("(modified)"));
+    assert!(frames[3].starts_with("######\n"));
+    assert!(frames[4].starts_with(" #####\n"));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        fs::read_to_string("assets/levels/test").unwrap()
+    );
+}
+
+// A full disk fails the write, the partial temporary file is removed
+#[cfg(target_os = "linux")]
+#[test]
+fn editor_removes_partial_writes() {
+    let path = copy_level("editor_removes_partial_writes");
+    let tmp = format!("{path}.tmp");
+    let _ = fs::remove_file(&tmp);
+    std::os::unix::fs::symlink("/dev/full", &tmp).unwrap();
+    let frames = run_editor(&editor_args(&path), [Action::UseTool, Action::Save], "");
+
+    assert!(frames[2].contains("Save failed"));
+    assert!(fs::symlink_metadata(&tmp).is_err());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        fs::read_to_string("assets/levels/test").unwrap()
+    );
+}
+
+#[test]
+fn editor_autosaves_and_restores() {
+    let path = copy_level("editor_autosaves");
+    let recovery = format!("{path}.recovery");
+    let _ = fs::remove_file(&recovery);
+    let args = Arguments {
+        autosave: Some(Duration::ZERO),
+        ..editor_args(&path)
+    };
+
+    // Quit without saving, the changes are kept for the next start
+    run_editor(
+        &args,
+        [Action::NextPalette, Action::NextPalette, Action::UseTool],
+        "",
+    );
+    assert_eq!(
+        fs::read_to_string(&recovery).unwrap(),
+        "O#####\n#+ Op#\n##**##\n######"
+    );
+
+    let frames = run_editor(&args, [Action::Save], "y");
+    assert!(frames[0].ends_with("Y - restore, N - discard"));
+    assert!(frames[1].starts_with("O#####\n"));
+    assert!(frames[1].contains("(modified)"));
+    assert!(fs::read_to_string(&path).unwrap().starts_with("O#####\n"));
+    assert!(fs::metadata(&recovery).is_err());
+
+    // Declining removes the recovery file
+    fs::write(&recovery, "#").unwrap();
+    let frames = run_editor(&args, [], "n");
+    assert!(frames[1].starts_with("O#####\n"));
+    assert!(fs::metadata(&recovery).is_err());
+
+    // Saving as another file leaves no recovery file of the old one behind
+    let other = format!("{path}_other");
+    let inputs = [Action::UseTool, Action::SaveAs];
+    run_editor(&args, inputs, &format!("{other}\n"));
+    assert!(fs::read_to_string(&other).unwrap().starts_with(" #####\n"));
+    assert!(fs::metadata(&recovery).is_err());
+
+    // Undoing the edits back to the saved level removes their autosave
+    let frames = run_editor(&args, [Action::UseTool, Action::Undo], "");
+    assert!(frames[1].contains("(modified)"));
+    assert!(!frames.last().unwrap().contains("Unsaved changes"));
+    assert!(fs::metadata(&recovery).is_err());
+
+    // An autosave of the level on disk isn't offered
+    fs::copy(&path, &recovery).unwrap();
+    let frames = run_editor(&args, [], "");
+    assert!(!frames[0].contains("Y - restore"));
+    assert!(fs::metadata(&recovery).is_err());
+}
+
+#[test]
+fn editor_survives_recovery_file_errors() {
+    let path = copy_level("editor_survives_recovery_file_errors");
+    let recovery = format!("{path}.recovery");
+    let args = Arguments {
+        autosave: Some(Duration::ZERO),
+        ..editor_args(&path)
+    };
+
+    // A directory in the way of the autosave is reported, the edit goes on
+    fs::create_dir_all(format!("{recovery}.tmp")).unwrap();
+    let frames = run_editor(&args, [Action::UseTool, Action::Undo], "");
+    let failed = format!("Autosave failed: Can't access `{recovery}.tmp`");
+    assert!(frames[2].contains(&failed));
+    assert!(frames[3].starts_with("######\n"));
+    fs::remove_dir(format!("{recovery}.tmp")).unwrap();
+
+    // So is one in the way of discarding the autosave
+    fs::create_dir_all(&recovery).unwrap();
+    let frames = run_editor(&args, [], "n");
+    let failed = format!("Discarding the autosave failed: Can't access `{recovery}`");
+    assert!(frames[1].contains(&failed));
+    fs::remove_dir(&recovery).unwrap();
+}
+
+#[test]
+fn editor_round_trips_levels() {
+    let levels = [
+        "; title: Round trip\n######\n#+~Op#  \n##**##\n######\n\n",
+        "######\r\n#+ Op#\r\n######\r\n",
+        "######\r\n#+ Op#\n######\r\n",
+        "  ####\n\t#+p#\n  ####",
+        "",
+    ];
+    // Wander past the edges of the level without painting
+    let mut inputs = vec![Action::Move(Direction::Right); 9];
+    inputs.extend([Action::Move(Direction::Down); 7]);
+    inputs.extend([Action::Move(Direction::Left); 9]);
+    inputs.push(Action::Save);
+
+    for (idx, level) in levels.iter().enumerate() {
+        let path = format!("{}/editor_round_trips_{idx}", env!("CARGO_TARGET_TMPDIR"));
+        fs::write(&path, level).unwrap();
+        let frames = run_editor(&editor_args(&path), inputs.clone(), "");
+        assert!(!frames.iter().any(|frame| frame.contains("(modified)")));
+        assert_eq!(fs::read_to_string(&path).unwrap(), *level);
+    }
+
+    // An edit only changes the painted cell
+    let path = format!("{}/editor_round_trips_edit", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&path, levels[0]).unwrap();
+    let inputs = [
+        Action::NextPalette,
+        Action::NextPalette,
+        Action::UseTool,
+        Action::Save,
+    ];
+    let frames = run_editor(&editor_args(&path), inputs, "");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        levels[0].replacen('#', "O", 1)
+    );
+    // The header stays out of the grid and the issues, which count its lines
+    assert!(frames[0].starts_with("######\n#+~Op#"));
+    assert!(frames[0].contains("first: line 3, column 3: unknown char `~`"));
+}
+
+#[test]
+fn save_round_trips() {
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let contents = "delay 150\npause yes\npaused_on_start no\nelapsed 42\nplayed 1234\ncurrent 1\n\
+        level first\nhash 00000000000000ff\nscore 1 1\nticks 9\nstate win\nrows 3\n###\n#p#\n###\n\
+        level second\nhash 0123456789abcdef\nscore 1 2\nticks 7\nstate none\nrows 4\n\
+        #####\n#p +#\n# O #\n#####\n";
+    let path = format!("{dir}/save_round_trips");
+    fs::write(&path, contents).unwrap();
+
+    let game = Game::load(&path).unwrap();
+    // The current level with its score, the delay and the pause
+    assert!(game.get_frame().starts_with("#####\n#p +#\n# O #\n#####\n"));
+    assert!(game
+        .get_frame()
+        .contains("Score: 1/2\nDelay: 150ms\nPaused: yes"));
+    // and every other value comes back as it was
+    let saved = format!("{dir}/save_round_trips_saved");
+    game.save(&saved).unwrap();
+    assert_eq!(fs::read_to_string(&saved).unwrap(), contents);
+}
+
+#[test]
+fn malformed_save_keeps_the_parse_error() {
+    let path = format!("{}/malformed_save", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&path, "delay soon\n").unwrap();
+    let args = Arguments {
+        resume_path: Some(path),
+        ..Default::default()
+    };
+
+    let Err(err) = Game::new(&args) else {
+        panic!("The save file should be rejected");
+    };
+    assert!(matches!(err, Error::Format { .. }));
+    let source = error::Error::source(&err).expect("The parse error is the source");
+    assert_eq!(source.to_string(), "invalid digit found in string");
+}
+
+#[test]
+fn run_with_checks_levels() {
+    let args = Arguments {
+        check: true,
+        level_paths: vec![
+            "assets/levels/test".into(),
+            "tests/fixtures/bdcff/not_bdcff.level".into(),
+        ],
+        ..Default::default()
+    };
+    let mut script = Script::default();
+    let result = boulder_dash::run_with(&args, &mut script);
+    assert!(matches!(
+        result,
+        Err(Error::Check {
+            invalid: 1,
+            total: 2
+        })
+    ));
+    assert!(script.get_frames().is_empty());
+}
+
+#[test]
+fn script_from_file() {
+    setup();
+    let path = format!("{}/script_from_file", env!("CARGO_TARGET_TMPDIR"));
+
+    fs::write(&path, "left restart # reload\n\ntoggle_pause quit").unwrap();
+    let mut script = Script::from_file(&path).unwrap();
+    Game::new(&game_args(Duration::ZERO))
+        .unwrap()
+        .run(&mut script)
+        .unwrap();
+    assert_eq!(script.get_frames().len(), 3);
+
+    fs::write(&path, "left jump").unwrap();
+    assert!(Script::from_file(&path).is_err());
+}
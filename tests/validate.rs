@@ -0,0 +1,54 @@
+use boulder_dash::validate;
+
+// Issues as `--check` prints them, with their line and column
+fn issues(level: &str) -> Vec<String> {
+    validate(level).iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn valid_level_has_no_issues() {
+    assert!(issues("#####\n#p+O#\n#####\n").is_empty());
+}
+
+#[test]
+fn players_are_counted() {
+    assert_eq!(issues("####\n#+ #\n####"), ["no player `p`"]);
+    assert_eq!(
+        issues("#####\n#pp+#\n#####"),
+        ["line 2, column 3: another player `p`, there can be only one"]
+    );
+}
+
+#[test]
+fn gems_are_required() {
+    assert_eq!(
+        issues("###\n#p#\n###"),
+        ["no gems `+`, the level can't be won"]
+    );
+}
+
+#[test]
+fn borders_are_closed() {
+    // A shorter row leaves the cell above it open
+    assert_eq!(
+        issues("#####\n#p+ #\n###"),
+        ["line 2, column 4: open border, surround the level with walls `#`"]
+    );
+    // So does the edge of the level
+    assert_eq!(
+        issues("#p+#\n####"),
+        [
+            "line 1, column 2: open border, surround the level with walls `#`",
+            "line 1, column 3: open border, surround the level with walls `#`",
+        ]
+    );
+}
+
+#[test]
+fn columns_count_leading_whitespace() {
+    // Rows are trimmed, the columns are still the ones in the file
+    assert_eq!(
+        issues("\n  #####\n \t#p?+#\n  #####"),
+        ["line 3, column 5: unknown char `?`"]
+    );
+}